@@ -0,0 +1,14 @@
+//! Shared helpers of the unix tests (e.g., spawning the shell scripts of the process tests).
+
+use crate::exec::{spawn_tokio, SpawnOptions};
+use crate::Result;
+use tokio::process::Child;
+
+/// Spawn `sh -c <script>` in its own process group, like the concurrent runners.
+pub fn spawn_sh(script: &str) -> Result<Child> {
+	let options = SpawnOptions {
+		own_process_group: true,
+		..Default::default()
+	};
+	spawn_tokio(None, "sh", &["-c", script], false, options)
+}
@@ -0,0 +1,103 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{check_safe_to_delete, run_action};
+use crate::config::Action;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_actions_check_safe_to_delete() -> Result<()> {
+	// -- Setup & Fixtures
+	let root_dir = Path::new(".");
+
+	// -- Exec & Check
+	assert!(check_safe_to_delete(root_dir, "src/_tests", Path::new("src/_tests")).is_ok());
+	for raw in ["/", "~", "~/.cargo", "", ".", "..", "../other", "src/../.."] {
+		assert!(
+			check_safe_to_delete(root_dir, raw, &root_dir.join(raw)).is_err(),
+			"'{raw}' should not be safe to delete"
+		);
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_actions_run_action() -> Result<()> {
+	// -- Setup & Fixtures
+	let root_dir = Path::new(".");
+	let dir = "target/tests/actions";
+	let _ = fs::remove_dir_all(dir);
+
+	// -- Exec
+	let actions = [
+		Action::Mkdir(vec![format!("{dir}/a")]),
+		Action::WriteFile {
+			path: format!("{dir}/a/one.txt"),
+			content: "one".to_string(),
+			append: false,
+		},
+		Action::WriteFile {
+			path: format!("{dir}/a/one.txt"),
+			content: "+1".to_string(),
+			append: true,
+		},
+		Action::Touch(vec![format!("{dir}/a/two.txt")]),
+		Action::Copy {
+			from: format!("{dir}/a"),
+			to: format!("{dir}/b"),
+		},
+		Action::Move {
+			from: format!("{dir}/b/two.txt"),
+			to: format!("{dir}/c/two.txt"),
+		},
+		Action::Rm(vec![format!("{dir}/a"), format!("{dir}/missing")]),
+	];
+	for action in actions.iter() {
+		run_action(action, root_dir, root_dir)?;
+	}
+
+	// -- Check
+	assert!(!Path::new(dir).join("a").exists());
+	assert_eq!(fs::read_to_string(format!("{dir}/b/one.txt"))?, "one+1");
+	assert!(!Path::new(dir).join("b/two.txt").exists());
+	assert!(Path::new(dir).join("c/two.txt").is_file());
+
+	fs::remove_dir_all(dir)?;
+
+	Ok(())
+}
+
+#[test]
+fn test_actions_copy_into_itself() -> Result<()> {
+	// -- Setup & Fixtures
+	let root_dir = Path::new(".");
+	let dir = "target/tests/actions_into_itself";
+	let _ = fs::remove_dir_all(dir);
+	fs::create_dir_all(format!("{dir}/a"))?;
+	fs::write(format!("{dir}/a/one.txt"), "one")?;
+
+	// -- Exec & Check
+	for to in [format!("{dir}/a/b"), format!("{dir}/a")] {
+		let copy = Action::Copy {
+			from: format!("{dir}/a"),
+			to: to.to_string(),
+		};
+		assert!(
+			run_action(&copy, root_dir, root_dir).is_err(),
+			"Should not copy '{dir}/a' into '{to}'"
+		);
+	}
+	let copy_file = Action::Copy {
+		from: format!("{dir}/a/one.txt"),
+		to: format!("{dir}/a/one.txt"),
+	};
+	assert!(run_action(&copy_file, root_dir, root_dir).is_err());
+	assert_eq!(fs::read_to_string(format!("{dir}/a/one.txt"))?, "one");
+	assert!(!Path::new(dir).join("a/b").exists());
+
+	fs::remove_dir_all(dir)?;
+
+	Ok(())
+}
@@ -0,0 +1,40 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::matching_items;
+use crate::config::parse_awesome_toml;
+
+#[test]
+fn test_completions_matching_items() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.build]]
+name = "web"
+cmd = "npm"
+
+[[runners.build]]
+name = "server"
+cmd = "cargo"
+
+[[runners.bench]]
+name = "all"
+cmd = "cargo"
+
+[[runner]]
+name = "bump"
+cmd = "cargo"
+"#;
+	let config = parse_awesome_toml(toml)?;
+	let refs = |current: &str| -> Vec<String> {
+		matching_items(&config, current).into_iter().map(|item| item.run_ref).collect()
+	};
+
+	// -- Exec & Check
+	assert_eq!(refs(""), ["bench", "build", "bump"]);
+	assert_eq!(refs("bu"), ["build", "bump"]);
+	assert_eq!(refs("build."), ["build.web", "build.server"]);
+	assert_eq!(refs("build.s"), ["build.server"]);
+	assert!(refs("nope.").is_empty());
+
+	Ok(())
+}
@@ -1,9 +1,10 @@
 pub type Result<T> = core::result::Result<T, Error>;
 pub type Error = Box<dyn std::error::Error>; // For early dev.
 
-use super::Config;
-use crate::config::parse_awesome_toml;
+use super::{Action, Color, Config, ConfirmDefault, ExitAction, RestartPolicy, Stdin, StopSignal};
+use crate::config::{parse_awesome_toml, resolve_inputs};
 use crate::utils::W;
+use std::time::Duration;
 
 const SIMPLE_AWESOME_TMPL: &str = r#"
 Some = "stuff"
@@ -125,3 +126,913 @@ fn test_parse_refed_awesome_toml() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_parse_ready_when_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "server"
+cmd = "cargo"
+args = ["run"]
+concurrent = true
+ready_when = { tcp = "localhost:8080", output = "Listening on", timeout = "10s" }
+
+[[runners.dev]]
+name = "pcss"
+cmd = "npm"
+concurrent = true
+ready_when.file = "dist/main.css"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check - server
+	let runner = config.get_grouped_runner("dev", "server").ok_or("Should have server")?;
+	let ready_when = runner.ready_when.as_ref().ok_or("Should have ready_when")?;
+	assert_eq!(ready_when.tcp.as_deref(), Some("localhost:8080"));
+	assert_eq!(ready_when.output.as_deref(), Some("Listening on"));
+	assert_eq!(ready_when.timeout, Duration::from_secs(10));
+
+	// -- Check - pcss (default timeout)
+	let runner = config.get_grouped_runner("dev", "pcss").ok_or("Should have pcss")?;
+	let ready_when = runner.ready_when.as_ref().ok_or("Should have ready_when")?;
+	assert_eq!(ready_when.file.as_deref(), Some("dist/main.css"));
+	assert_eq!(ready_when.timeout, Duration::from_secs(30));
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_output_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[output]
+prefix = "{name} |"
+timestamps = true
+
+[[runners.dev]]
+name = "web"
+cmd = "npm"
+concurrent = true
+color = "magenta"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	assert_eq!(config.output.prefix, "{name} |");
+	assert!(config.output.timestamps);
+	assert!(config.output.color, "color should default to true");
+	assert_eq!(config.output.timestamp_format, "%H:%M:%S");
+	let runner = config.get_grouped_runner("dev", "web").ok_or("Should have web")?;
+	assert!(matches!(runner.color, Some(Color::Magenta)));
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_restart_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "web"
+cmd = "npm"
+concurrent = true
+restart = "on-failure"
+max_restarts = 3
+restart_backoff = 500
+
+[[runners.dev]]
+name = "pcss"
+cmd = "npm"
+concurrent = true
+
+[[runners.dev]]
+name = "api"
+cmd = "cargo"
+concurrent = true
+restart = "always"
+restart_backoff = "2s"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check - web
+	let runner = config.get_grouped_runner("dev", "web").ok_or("Should have web")?;
+	assert_eq!(runner.restart, RestartPolicy::OnFailure);
+	assert_eq!(runner.max_restarts, 3);
+	assert_eq!(runner.restart_delay(0).as_millis(), 500);
+	assert_eq!(runner.restart_delay(2).as_millis(), 2000);
+
+	// -- Check - pcss (defaults)
+	let runner = config.get_grouped_runner("dev", "pcss").ok_or("Should have pcss")?;
+	assert_eq!(runner.restart, RestartPolicy::No);
+
+	// -- Check - api (humantime backoff)
+	let runner = config.get_grouped_runner("dev", "api").ok_or("Should have api")?;
+	assert_eq!(runner.restart_delay(1), Duration::from_secs(4));
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_on_exit_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "web"
+cmd = "npm"
+concurrent = true
+on_exit = { success = "end_all", failure = { run = "dev.cleanup" } }
+
+[[runners.dev]]
+name = "cleanup"
+cmd = "rm"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("dev", "web").ok_or("Should have web")?;
+	let on_exit = runner.on_exit.as_ref().ok_or("Should have on_exit")?;
+	assert_eq!(on_exit.success, ExitAction::EndAll);
+	assert_eq!(on_exit.failure, ExitAction::Run("dev.cleanup".to_string()));
+	let runners = config.get_runners_for_ref("dev.cleanup").ok_or("Should resolve dev.cleanup")?;
+	assert_eq!(runners.len(), 1);
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_stop_signal_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "web"
+cmd = "npm"
+concurrent = true
+stop_signal = "SIGINT"
+stop_timeout = "2s"
+
+[[runners.dev]]
+name = "pcss"
+cmd = "npm"
+concurrent = true
+"#;
+	let toml_invalid = r#"
+[[runners.dev]]
+name = "web"
+cmd = "npm"
+stop_signal = "SIGFOO"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("dev", "web").ok_or("Should have web")?;
+	assert_eq!(runner.stop_signal, StopSignal::Int);
+	assert_eq!(runner.stop_timeout, Duration::from_secs(2));
+	let runner = config.get_grouped_runner("dev", "pcss").ok_or("Should have pcss")?;
+	assert_eq!(runner.stop_signal, StopSignal::Term);
+	assert_eq!(runner.stop_timeout, Duration::from_secs(5));
+	assert!(parse_awesome_toml(toml_invalid).is_err(), "SIGFOO should not be supported");
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_continue_on_error_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runner]]
+name = "solo"
+cmd = "ls"
+
+[[runners.build]]
+name = "lint"
+cmd = "cargo"
+args = ["clippy"]
+continue_on_error = true
+
+[[runners.build]]
+name = "test"
+cmd = "cargo"
+args = ["test"]
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("build", "lint").ok_or("Should have lint")?;
+	assert!(runner.continue_on_error);
+	assert_eq!(runner.run_ref(), "build.lint");
+	let runner = config.get_grouped_runner("build", "test").ok_or("Should have test")?;
+	assert!(!runner.continue_on_error);
+	let runner = config.get_solo_runner("solo").ok_or("Should have solo")?;
+	assert_eq!(runner.run_ref(), "solo");
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_timeout_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.test]]
+name = "integration"
+cmd = "cargo"
+args = ["test"]
+timeout = "5m"
+
+[[runners.test]]
+name = "unit"
+cmd = "cargo"
+timeout = 1500
+
+[[runners.test]]
+name = "other"
+cmd = "cargo"
+"#;
+	let toml_invalid = r#"
+[[runners.test]]
+name = "integration"
+cmd = "cargo"
+timeout = "5 parsecs"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config
+		.get_grouped_runner("test", "integration")
+		.ok_or("Should have integration")?;
+	assert_eq!(runner.timeout, Some(Duration::from_secs(300)));
+	let runner = config.get_grouped_runner("test", "unit").ok_or("Should have unit")?;
+	assert_eq!(runner.timeout, Some(Duration::from_millis(1500)));
+	let runner = config.get_grouped_runner("test", "other").ok_or("Should have other")?;
+	assert_eq!(runner.timeout, None);
+	assert!(
+		parse_awesome_toml(toml_invalid).is_err(),
+		"'5 parsecs' should not be a valid duration"
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_retry_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.setup]]
+name = "npm_install"
+cmd = "npm"
+args = ["install"]
+retry = { attempts = 3, delay = "2s", backoff = 2.0, on_codes = [1] }
+
+[[runners.setup]]
+name = "db_tests"
+cmd = "cargo"
+args = ["test"]
+retry = { delay = 500 }
+"#;
+	let timeout_err = crate::Error::ExecTimeout("npm install".to_string(), Duration::from_secs(1));
+	let other_err = crate::Error::AwesomTomlNotFound;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config
+		.get_grouped_runner("setup", "npm_install")
+		.ok_or("Should have npm_install")?;
+	let retry = runner.retry.as_ref().ok_or("Should have retry")?;
+	assert_eq!(retry.attempts, 3);
+	assert_eq!(retry.delay, Duration::from_secs(2));
+	assert_eq!(retry.on_codes, vec![1]);
+	// on_codes given, so a timeout is not retried
+	assert_eq!(retry.next_delay(1, &timeout_err), None);
+
+	let runner = config.get_grouped_runner("setup", "db_tests").ok_or("Should have db_tests")?;
+	let retry = runner.retry.as_ref().ok_or("Should have retry")?;
+	assert_eq!(retry.attempts, 3);
+	assert_eq!(retry.next_delay(1, &timeout_err), Some(Duration::from_millis(500)));
+	assert_eq!(retry.next_delay(2, &timeout_err), Some(Duration::from_millis(1000)));
+	assert_eq!(retry.next_delay(3, &timeout_err), None, "attempts exhausted");
+	assert_eq!(retry.next_delay(1, &other_err), None, "not an exec failure");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_parse_success_codes_and_output_checks_awesome_toml() -> Result<()> {
+	use std::os::unix::process::ExitStatusExt;
+	use std::process::ExitStatus;
+
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.check]]
+name = "fmt_check"
+cmd = "some_tool"
+success_codes = [0, 1]
+
+[[runners.check]]
+name = "smoke"
+cmd = "curl"
+args = ["-s", "localhost:8080/health"]
+fail_on_output = "ERROR"
+expect_output = "ok"
+"#;
+	// Note: The unix wait status has the exit code in the second byte.
+	let exit_0 = ExitStatus::from_raw(0);
+	let exit_1 = ExitStatus::from_raw(1 << 8);
+	let exit_2 = ExitStatus::from_raw(2 << 8);
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("check", "fmt_check").ok_or("Should have fmt_check")?;
+	assert!(runner.is_success(&exit_0));
+	assert!(runner.is_success(&exit_1));
+	assert!(!runner.is_success(&exit_2));
+
+	let runner = config.get_grouped_runner("check", "smoke").ok_or("Should have smoke")?;
+	assert!(!runner.is_success(&exit_1));
+	let checks = runner.output_checks()?;
+	assert!(checks.fail_on.is_some());
+	// no output line, so the expect_output fails
+	let err = runner
+		.check_exit(&exit_0, &checks)
+		.err()
+		.ok_or("Should fail on expect_output")?;
+	assert!(matches!(err, crate::Error::OutputNotExpected(_)));
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_capture_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.release]]
+name = "git_sha"
+cmd = "git"
+args = ["rev-parse", "--short", "HEAD"]
+capture = "GIT_SHA"
+
+[[runners.release]]
+name = "version"
+cmd = "cargo"
+args = ["metadata", "--format-version", "1", "--no-deps"]
+capture = { var = "VERSION", json = "/packages/0/version", regex = '^(\d+\.\d+)' }
+
+[[runners.release]]
+name = "tag"
+cmd = "git"
+args = ["tag", "v{{VERSION}}-{{ GIT_SHA }}"]
+"#;
+	let mut vars = crate::utils::Vars::new();
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("release", "git_sha").ok_or("Should have git_sha")?;
+	let capture = runner.capture.as_ref().ok_or("Should have capture")?;
+	assert_eq!(capture.var, "GIT_SHA");
+	vars.insert("GIT_SHA".to_string(), capture.extract("  a1b2c3d\n")?);
+
+	let runner = config.get_grouped_runner("release", "version").ok_or("Should have version")?;
+	let capture = runner.capture.as_ref().ok_or("Should have capture")?;
+	assert_eq!(capture.var, "VERSION");
+	vars.insert(
+		"VERSION".to_string(),
+		capture.extract(r#"{"packages": [{"version": "1.2.3"}]}"#)?,
+	);
+	assert!(capture.extract("not json").is_err());
+
+	let runner = config.get_grouped_runner("release", "tag").ok_or("Should have tag")?;
+	let args: Vec<&str> = W(&runner.args).into();
+	assert_eq!(crate::utils::render_template(args[1], &vars), "v1.2-a1b2c3d");
+	assert_eq!(crate::utils::render_template("{{UNKNOWN}}", &vars), "{{UNKNOWN}}");
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_stdio_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.data]]
+name = "dump"
+cmd = "psql"
+stdin = "null"
+stdout = "out/users.txt"
+stderr = "out/errors.txt"
+
+[[runners.data]]
+name = "load"
+cmd = "psql"
+stdin = { file = "sql/seed.sql" }
+
+[[runners.data]]
+name = "query"
+cmd = "psql"
+stdin = { text = "select 1;" }
+
+[[runners.data]]
+name = "count"
+cmd = "grep"
+stdin = { from = "data.load" }
+
+[[runners.data]]
+name = "edit"
+cmd = "vim"
+stdin = "inherit"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let stdin_of = |name: &str| config.get_grouped_runner("data", name).and_then(|r| r.stdin.clone());
+	assert_eq!(stdin_of("dump"), Some(Stdin::Null));
+	assert_eq!(stdin_of("load"), Some(Stdin::File("sql/seed.sql".to_string())));
+	assert_eq!(stdin_of("query"), Some(Stdin::Text("select 1;".to_string())));
+	assert_eq!(stdin_of("count"), Some(Stdin::From("data.load".to_string())));
+	assert_eq!(stdin_of("edit"), Some(Stdin::Inherit));
+
+	let runner = config.get_grouped_runner("data", "dump").ok_or("Should have dump")?;
+	assert_eq!(runner.stdout.as_deref(), Some("out/users.txt"));
+	assert_eq!(runner.stderr.as_deref(), Some("out/errors.txt"));
+
+	// invalid stdin
+	let toml = r#"
+[[runner]]
+name = "bad"
+cmd = "cat"
+stdin = "terminal"
+"#;
+	assert!(parse_awesome_toml(toml).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_matrix_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.build]]
+name = "cargo"
+cmd = "cargo"
+args = ["build", "--target", "{{target}}", "--profile", "{{profile}}", "{{GIT_SHA}}"]
+env = { CARGO_TARGET_DIR = "target/{{target}}" }
+working_dir = "crates/{{profile}}"
+matrix = { target = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"], profile = ["dev", "release"] }
+parallel = 2
+
+[[runners.build]]
+name = "after"
+cmd = "echo"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runners = config
+		.get_runners_for_ref("build.cargo")
+		.ok_or("Should have build.cargo entries")?;
+	let names: Vec<&str> = runners.iter().map(|r| r.name.as_str()).collect();
+	assert_eq!(
+		names,
+		[
+			"cargo[target=x86_64-unknown-linux-gnu,profile=dev]",
+			"cargo[target=x86_64-unknown-linux-gnu,profile=release]",
+			"cargo[target=wasm32-unknown-unknown,profile=dev]",
+			"cargo[target=wasm32-unknown-unknown,profile=release]",
+		]
+	);
+
+	let runner = runners[3];
+	assert_eq!(runner.matrix_base_name(), Some("cargo"));
+	assert_eq!(runner.parallel, 2);
+	let args: Vec<&str> = W(&runner.args).into();
+	// Note: The unknown vars (e.g., session vars) are left for the exec.
+	assert_eq!(
+		args,
+		[
+			"build",
+			"--target",
+			"wasm32-unknown-unknown",
+			"--profile",
+			"release",
+			"{{GIT_SHA}}"
+		]
+	);
+	let env = runner.env.as_ref().ok_or("Should have env")?;
+	assert_eq!(env["CARGO_TARGET_DIR"], "target/wasm32-unknown-unknown");
+	assert_eq!(runner.working_dir.as_deref(), Some("crates/release"));
+
+	let runners = config.get_runners("build").ok_or("Should have build")?;
+	assert_eq!(runners.len(), 5);
+	assert!(runners[4].matrix_entry.is_none());
+
+	// invalid matrix
+	let toml = r#"
+[[runner]]
+name = "bad"
+cmd = "echo"
+matrix = { target = [] }
+"#;
+	assert!(parse_awesome_toml(toml).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_for_each_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.db]]
+name = "migrate"
+cmd = "psql"
+args = ["-f", "{{item}}"]
+for_each = { glob = "migrations/*.sql" }
+parallel = 4
+
+[[runners.db]]
+name = "protos"
+cmd = "protoc"
+args = ["{{ item }}"]
+for_each = { cmd = "printf 'a.proto\n\n  b.proto \n'", batch = 10 }
+
+[[runners.db]]
+name = "bad"
+cmd = "echo"
+for_each = { batch = 2 }
+"#;
+	let vars = crate::utils::Vars::new();
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runner = config.get_grouped_runner("db", "migrate").ok_or("Should have migrate")?;
+	let for_each = runner.for_each.as_ref().ok_or("Should have for_each")?;
+	assert_eq!(for_each.glob.as_deref(), Some("migrations/*.sql"));
+	assert_eq!(for_each.batch, 1);
+	assert_eq!(runner.parallel, 4);
+
+	let runner = config.get_grouped_runner("db", "protos").ok_or("Should have protos")?;
+	let for_each = runner.for_each.as_ref().ok_or("Should have for_each")?;
+	assert_eq!(for_each.batch, 10);
+	let args: Vec<&str> = W(&runner.args).into();
+	assert!(crate::utils::has_template_var(args[0], "item"));
+	assert!(!crate::utils::has_template_var("{{items}}", "item"));
+	#[cfg(unix)]
+	assert_eq!(for_each.items(&runner.name, &vars)?, ["a.proto", "b.proto"]);
+
+	let runner = config.get_grouped_runner("db", "bad").ok_or("Should have bad")?;
+	let for_each = runner.for_each.as_ref().ok_or("Should have for_each")?;
+	assert!(for_each.items(&runner.name, &vars).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_mutex_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.build]]
+name = "cargo"
+cmd = "cargo"
+matrix = { target = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"] }
+mutex = ["cargo-{{target}}", "db"]
+parallel = 2
+
+[[runners.build]]
+name = "migrate"
+cmd = "psql"
+for_each = { glob = "migrations/*.sql" }
+mutex = ["db-{{item}}", "db"]
+"#;
+	let vars = crate::utils::Vars::new();
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runners = config.get_runners_for_ref("build.cargo").ok_or("Should have build.cargo")?;
+	let names: Vec<String> = runners[1].mutex_names(&vars, &[]).into_iter().collect();
+	assert_eq!(names, ["cargo-wasm32-unknown-unknown", "db"]);
+
+	let runner = config.get_grouped_runner("build", "migrate").ok_or("Should have migrate")?;
+	let names: Vec<String> = runner.mutex_names(&vars, &["001.sql".to_string()]).into_iter().collect();
+	assert_eq!(names, ["db", "db-001.sql"]);
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_hooks_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[hooks.dev]
+before = ["dev.db_up"]
+finally = ["dev.db_down", "cleanup"]
+
+[[runners.dev]]
+name = "server"
+cmd = "cargo"
+args = ["run"]
+concurrent = true
+
+[[runner]]
+name = "cleanup"
+cmd = "rm"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let hooks = config.get_hooks_for_ref("dev.server").ok_or("Should have dev hooks")?;
+	assert_eq!(hooks.before, ["dev.db_up"]);
+	assert!(hooks.after.is_empty());
+	assert!(hooks.on_failure.is_empty());
+	assert_eq!(hooks.finally, ["dev.db_down", "cleanup"]);
+	assert!(config.get_hooks_for_ref("dev").is_some());
+	assert!(config.get_hooks_for_ref("cleanup").is_none());
+
+	// invalid hooks
+	assert!(parse_awesome_toml("[hooks.dev]\nbefore = \"dev.db_up\"").is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_action_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.clean]]
+name = "rm_dist"
+action = "rm"
+path = "dist"
+paths = ["target/tmp"]
+
+[[runners.clean]]
+name = "copy_assets"
+action = "copy"
+from = "assets"
+to = "dist/assets"
+
+[[runners.clean]]
+name = "version"
+action = "write_file"
+path = "dist/VERSION"
+content = "{{VERSION}}"
+append = true
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runners = config.get_runners_for_ref("clean").ok_or("Should have clean runners")?;
+	assert_eq!(
+		runners[0].action,
+		Some(Action::Rm(vec!["dist".into(), "target/tmp".into()]))
+	);
+	assert_eq!(
+		runners[1].action,
+		Some(Action::Copy {
+			from: "assets".into(),
+			to: "dist/assets".into()
+		})
+	);
+	assert_eq!(
+		runners[2].action,
+		Some(Action::WriteFile {
+			path: "dist/VERSION".into(),
+			content: "{{VERSION}}".into(),
+			append: true
+		})
+	);
+
+	// invalid actions
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\naction = \"copy\"\nfrom = \"a\"").is_err());
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\naction = \"rm\"").is_err());
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\naction = \"chmod\"\npath = \"a\"").is_err());
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"").is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_wait_for_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "wait_db"
+wait_for = { tcp = "localhost:5432", timeout = "1m" }
+
+[[runners.dev]]
+name = "wait_api"
+wait_for = { http = "http://127.0.0.1:8080/health", status = 204, interval = 500 }
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+	let wait_db = runners[0].wait_for.as_ref().ok_or("Should have wait_for")?;
+	assert_eq!(wait_db.tcp.as_deref(), Some("localhost:5432"));
+	assert_eq!(wait_db.timeout, Duration::from_secs(60));
+	assert_eq!(wait_db.interval, Duration::from_millis(200));
+	let wait_api = runners[1].wait_for.as_ref().ok_or("Should have wait_for")?;
+	assert_eq!(wait_api.http.as_deref(), Some("http://127.0.0.1:8080/health"));
+	assert_eq!(wait_api.status, Some(204));
+	assert_eq!(wait_api.interval, Duration::from_millis(500));
+
+	// invalid wait_for
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\nwait_for = { timeout = \"5s\" }").is_err());
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\nwait_for = { http = \"https://example.com\" }").is_err());
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\nwait_for = { file = \"a\", status = 200 }").is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_confirm_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.db]]
+name = "reset"
+confirm = "Reset local database?"
+cmd = "sqlx"
+args = ["database", "reset"]
+
+[[runners.db]]
+name = "drop"
+confirm = "Drop the database?"
+confirm_default = "abort"
+cmd = "sqlx"
+args = ["database", "drop"]
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	let runners = config.get_runners_for_ref("db").ok_or("Should have db runners")?;
+	assert_eq!(runners[0].confirm.as_deref(), Some("Reset local database?"));
+	assert_eq!(runners[0].confirm_default, ConfirmDefault::Skip);
+	assert_eq!(runners[1].confirm_default, ConfirmDefault::Abort);
+
+	// invalid confirm_default
+	assert!(parse_awesome_toml("[[runner]]\nname = \"x\"\ncmd = \"ls\"\nconfirm_default = \"maybe\"").is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_resolve_inputs() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runner]]
+name = "gen"
+inputs = [
+	{ name = "component", prompt = "Component name?", default = "Button" },
+	{ name = "env", choices = ["dev", "staging"] },
+]
+cmd = "echo"
+args = ["{{component}}", "{{env}}"]
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+	let runner = config.get_solo_runner("gen").ok_or("Should have gen")?;
+	let inputs: Vec<_> = runner.inputs.iter().collect();
+	let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+	let env_staging = vec![("env".to_string(), "staging".to_string())];
+
+	// -- Exec
+	let by_name = resolve_inputs(&inputs, &env_staging, &[], false)?;
+	let by_position = resolve_inputs(&inputs, &[], &args(&["Card", "dev"]), false)?;
+	let mixed = resolve_inputs(&inputs, &env_staging, &args(&["Card"]), false)?;
+
+	// -- Check
+	assert_eq!(by_name.get("component").map(String::as_str), Some("Button"));
+	assert_eq!(by_name.get("env").map(String::as_str), Some("staging"));
+	assert_eq!(by_position.get("component").map(String::as_str), Some("Card"));
+	assert_eq!(by_position.get("env").map(String::as_str), Some("dev"));
+	assert_eq!(mixed.get("component").map(String::as_str), Some("Card"));
+	assert_eq!(mixed.get("env").map(String::as_str), Some("staging"));
+
+	// missing, not a choice, unknown name, too many args
+	assert!(resolve_inputs(&inputs, &[], &[], false).is_err());
+	assert!(resolve_inputs(&inputs, &[], &args(&["Card", "prod"]), false).is_err());
+	assert!(resolve_inputs(&inputs, &[("foo".to_string(), "x".to_string())], &[], false).is_err());
+	assert!(resolve_inputs(&inputs, &[], &args(&["Card", "dev", "x"]), false).is_err());
+
+	// invalid input definition
+	let toml = "[[runner]]\nname = \"x\"\ncmd = \"ls\"\ninputs = [{ name = \"env\", default = \"prod\", choices = [\"dev\"] }]";
+	assert!(parse_awesome_toml(toml).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_default_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+default = "dev"
+
+[[runners.dev]]
+name = "server"
+cmd = "cargo"
+"#;
+
+	// -- Exec
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Check
+	assert_eq!(config.default.as_deref(), Some("dev"));
+	assert!(parse_awesome_toml(SIMPLE_AWESOME_TMPL)?.default.is_none());
+	assert!(parse_awesome_toml("default = 1").is_err());
+
+	Ok(())
+}
+
+#[test]
+fn test_config_run_ref_not_found() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "server"
+cmd = "cargo"
+
+[[runners.dev]]
+name = "build"
+cmd = "cargo"
+args = ["build", "--target", "{{target}}"]
+matrix = { target = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"] }
+
+[[runner]]
+name = "lint"
+cmd = "cargo"
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Exec & Check
+	let message = |run_ref: &str| config.run_ref_not_found(run_ref).to_string();
+	assert_eq!(message("dve"), "No runners found for 'dve'. Did you mean 'dev'?");
+	assert_eq!(message("lnt"), "No runners found for 'lnt'. Did you mean 'lint'?");
+	assert_eq!(
+		message("sever"),
+		"No runners found for 'sever'. Did you mean 'dev.server'?"
+	);
+	assert_eq!(
+		message("dev.buidl"),
+		"No runners found for 'dev.buidl'. Did you mean 'dev.build'? Runners of group 'dev': server, build."
+	);
+	assert_eq!(
+		message("dev.zzz"),
+		"No runners found for 'dev.zzz'. Runners of group 'dev': server, build."
+	);
+	assert_eq!(message("nothing_like_it"), "No runners found for 'nothing_like_it'.");
+
+	Ok(())
+}
+
+#[test]
+fn test_parse_reserved_run_ref_awesome_toml() -> Result<()> {
+	// -- Setup & Fixtures
+	let group = "[[runners.logs]]\nname = \"tail\"\ncmd = \"tail\"";
+	let solo = "[[runner]]\nname = \"completions\"\ncmd = \"echo\"";
+	let grouped = "[[runners.dev]]\nname = \"logs\"\ncmd = \"tail\"";
+
+	// -- Exec & Check
+	let err = parse_awesome_toml(group).err().ok_or("Should fail on group 'logs'")?;
+	assert_eq!(
+		err.to_string(),
+		"'logs' is reserved for the 'awesome logs' command, rename the group or runner"
+	);
+	assert!(
+		parse_awesome_toml(solo).is_err(),
+		"Should fail on solo runner 'completions'"
+	);
+	// Note: A grouped runner is reached as `dev.logs`, so its name is not reserved.
+	assert!(parse_awesome_toml(grouped).is_ok());
+
+	Ok(())
+}
@@ -0,0 +1,48 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{spawn_and_wait, InFlight, SpawnOptions, Stop};
+use crate::config::StopSignal;
+use std::fs;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_exec_in_flight_terminate_all() -> Result<()> {
+	use nix::sys::signal::kill;
+	use nix::unistd::Pid;
+
+	// -- Setup & Fixtures
+	let dir = "target/tests/exec";
+	let pid_file = format!("{dir}/in_flight_pid.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&pid_file);
+	let in_flight = InFlight::default();
+	let stop = Stop {
+		name: "build".to_string(),
+		at: None,
+		stop_signal: StopSignal::Term,
+		stop_timeout: Duration::from_secs(2),
+		in_flight: in_flight.clone(),
+	};
+	let script = format!("echo $$ > {pid_file}; sleep 100");
+	let args = ["-c", script.as_str()];
+
+	// -- Exec
+	// Note: Dropped while waiting, like the session start on Ctrl-C.
+	let wait = spawn_and_wait(None, "sh", &args, false, None, stop, SpawnOptions::default());
+	assert!(timeout(Duration::from_millis(300), wait).await.is_err());
+	let started = Instant::now();
+	in_flight.terminate_all().await;
+
+	// -- Check
+	let pid: i32 = fs::read_to_string(&pid_file)?.trim().parse()?;
+	assert!(kill(Pid::from_raw(pid), None).is_err(), "Process should have exited");
+	assert!(
+		started.elapsed() < Duration::from_secs(1),
+		"Should stop on the stop_signal, before stop_timeout"
+	);
+
+	Ok(())
+}
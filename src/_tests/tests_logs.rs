@@ -0,0 +1,57 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{find_log_files, RunnerLog};
+use crate::config::LogsConfig;
+use std::fs;
+use std::path::PathBuf;
+
+fn test_root(name: &str) -> Result<PathBuf> {
+	let root = std::env::temp_dir().join(format!("awesome-tests-{name}-{}", std::process::id()));
+	if root.exists() {
+		fs::remove_dir_all(&root)?;
+	}
+	fs::create_dir_all(&root)?;
+	Ok(root)
+}
+
+#[test]
+fn test_logs_rotate() -> Result<()> {
+	// -- Setup & Fixtures
+	let root = test_root("rotate")?;
+	let logs_config = LogsConfig {
+		max_size: 100,
+		max_files: 2,
+		..Default::default()
+	};
+
+	// -- Exec
+	let mut log = RunnerLog::open(&root, &logs_config, Some("dev"), "web")?;
+	for i in 0..20 {
+		log.write_line(&format!("line {i} - 0123456789"))?;
+	}
+
+	// -- Check
+	let logs_dir = root.join(&logs_config.dir);
+	let dev_dir = logs_dir.join("dev");
+	assert!(dev_dir.join("web.log").is_file());
+	assert!(dev_dir.join("web.log.1").is_file());
+	assert!(dev_dir.join("web.log.2").is_file());
+	assert!(!dev_dir.join("web.log.3").exists(), "Should only keep max_files rotated files");
+	assert!(fs::metadata(dev_dir.join("web.log"))?.len() <= 100);
+	let last = fs::read_to_string(dev_dir.join("web.log"))?;
+	assert!(last.ends_with("line 19 - 0123456789\n"));
+
+	// -- Check - find_log_files for group and group.runner
+	let files = find_log_files(&logs_dir, "dev")?;
+	assert_eq!(files.len(), 1, "Rotated files should not be listed");
+	assert_eq!(files[0].0, "web");
+	let files = find_log_files(&logs_dir, "dev.web")?;
+	assert_eq!(files.len(), 1);
+	let files = find_log_files(&logs_dir, "dev.nope")?;
+	assert!(files.is_empty());
+
+	fs::remove_dir_all(&root)?;
+
+	Ok(())
+}
@@ -0,0 +1,49 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::pick_items;
+use crate::config::parse_awesome_toml;
+
+#[test]
+fn test_picker_pick_items() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.dev]]
+name = "server"
+description = "Start the dev server"
+cmd = "cargo"
+args = ["run"]
+
+[[runners.dev]]
+name = "build"
+cmd = "cargo"
+args = ["build", "--target", "{{target}}"]
+matrix = { target = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"] }
+
+[[runners.check]]
+name = "clippy"
+cmd = "cargo"
+
+[[runner]]
+name = "lint"
+description = "Run clippy"
+cmd = "cargo"
+"#;
+	let config = parse_awesome_toml(toml)?;
+
+	// -- Exec
+	let items = pick_items(&config);
+
+	// -- Check
+	let refs: Vec<&str> = items.iter().map(|item| item.run_ref.as_str()).collect();
+	assert_eq!(
+		refs,
+		["check", "check.clippy", "dev", "dev.server", "dev.build", "lint"]
+	);
+	assert_eq!(items[2].description, "runs server, build");
+	assert_eq!(items[3].description, "Start the dev server");
+	assert_eq!(items[4].description, "");
+	assert_eq!(items[5].description, "Run clippy");
+
+	Ok(())
+}
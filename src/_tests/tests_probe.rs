@@ -0,0 +1,65 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{http_status, parse_http_url};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_probe_parse_http_url() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_urls = [
+		("http://localhost:8080/health", ("localhost:8080", "/health")),
+		("http://localhost", ("localhost:80", "/")),
+		("http://localhost/api?ready=1", ("localhost:80", "/api?ready=1")),
+		("http://localhost:3000?ready=1", ("localhost:3000", "/?ready=1")),
+		("http://localhost:3000/app#top", ("localhost:3000", "/app")),
+		("http://[::1]:8080/health", ("[::1]:8080", "/health")),
+		("http://[::1]", ("[::1]:80", "/")),
+	];
+
+	// -- Exec & Check
+	for (url, (addr, path)) in fx_urls {
+		let parsed = parse_http_url(url).ok_or_else(|| format!("Should parse '{url}'"))?;
+		assert_eq!(parsed, (addr.to_string(), path.to_string()), "For '{url}'");
+	}
+	for url in ["https://localhost", "localhost:8080", "http://", "http:///health"] {
+		assert!(parse_http_url(url).is_none(), "Should not parse '{url}'");
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_probe_http_status() -> Result<()> {
+	// -- Setup & Fixtures
+	let listener = TcpListener::bind("127.0.0.1:0").await?;
+	let addr = listener.local_addr()?;
+	let server = tokio::spawn(async move {
+		let (mut stream, _) = listener.accept().await?;
+		let mut request = vec![0; 1024];
+		let len = stream.read(&mut request).await?;
+		stream
+			.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n")
+			.await?;
+		Ok::<_, std::io::Error>(String::from_utf8_lossy(&request[..len]).to_string())
+	});
+
+	// -- Exec
+	let status = http_status(&format!("http://{addr}/health?full=1")).await;
+
+	// -- Check
+	assert_eq!(status, Some(503));
+	let request = server.await??;
+	assert!(
+		request.starts_with("GET /health?full=1 HTTP/1.1\r\n"),
+		"Should request the path with the query"
+	);
+	assert_eq!(
+		http_status("http://127.0.0.1:1/").await,
+		None,
+		"Should be None when refused"
+	);
+
+	Ok(())
+}
@@ -0,0 +1,80 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{terminate_process_tree, ProcTree};
+use crate::config::StopSignal;
+use crate::exec::{spawn_tokio, SpawnOptions};
+#[cfg(unix)]
+use crate::test_support::spawn_sh;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_proc_tree_terminate_no_survivors() -> Result<()> {
+	// -- Setup & Fixtures
+	let mut child = spawn_sh("sleep 100 & wait")?;
+	let tree = ProcTree::new(child.id().ok_or("Should have a pid")?);
+	// Note: Give the shell the time to spawn the background sleep.
+	sleep(Duration::from_millis(100)).await;
+	let started = Instant::now();
+
+	// -- Exec
+	terminate_process_tree("tree", &mut child, StopSignal::Term, Duration::from_secs(2)).await;
+
+	// -- Check
+	assert!(!tree.is_alive(), "No process of the tree should survive");
+	assert!(
+		started.elapsed() < Duration::from_secs(1),
+		"Tree should stop on the stop_signal, before stop_timeout"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_proc_tree_terminate_escalate_to_kill() -> Result<()> {
+	// -- Setup & Fixtures
+	let stop_timeout = Duration::from_millis(300);
+	let mut child = spawn_sh("trap '' TERM; sleep 100 & wait")?;
+	let tree = ProcTree::new(child.id().ok_or("Should have a pid")?);
+	// Note: Give the shell the time to set the trap.
+	sleep(Duration::from_millis(100)).await;
+	let started = Instant::now();
+
+	// -- Exec
+	terminate_process_tree("stubborn", &mut child, StopSignal::Term, stop_timeout).await;
+
+	// -- Check
+	assert!(
+		started.elapsed() >= stop_timeout,
+		"Should wait the stop_timeout before killing"
+	);
+	assert!(!tree.is_alive(), "No process of the tree should survive the SIGKILL");
+	let status = child.try_wait()?.ok_or("Child should be reaped")?;
+	assert!(!status.success());
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_proc_tree_terminate_not_group_leader() -> Result<()> {
+	// -- Setup & Fixtures
+	// Note: Like the sequential runners, in the group of the tests (which must not be signaled).
+	let mut child = spawn_tokio(None, "sh", &["-c", "sleep 100 & wait"], false, SpawnOptions::default())?;
+	// Note: Give the shell the time to spawn the background sleep.
+	sleep(Duration::from_millis(100)).await;
+	let tree = ProcTree::new(child.id().ok_or("Should have a pid")?);
+
+	// -- Exec
+	terminate_process_tree("sequential", &mut child, StopSignal::Term, Duration::from_secs(2)).await;
+
+	// -- Check
+	assert!(tree.pgid.is_none(), "Should not be a process group");
+	assert_eq!(tree.pids.len(), 2, "Should have the shell and its sleep");
+	assert!(!tree.is_alive(), "No descendant should survive");
+
+	Ok(())
+}
@@ -0,0 +1,797 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{input_vars, run_runners, RunOptions};
+use crate::config::{parse_awesome_toml, Config};
+use crate::utils::Vars;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_restart_max_restarts() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let runs_file = format!("{dir}/restart_runs.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&runs_file);
+	let toml = format!(
+		r#"
+[[runners.dev]]
+name = "flaky"
+cmd = "sh"
+args = ["-c", "echo run >> {runs_file}; exit 1"]
+concurrent = true
+restart = "on-failure"
+max_restarts = 2
+restart_backoff = 10
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	let runs = fs::read_to_string(&runs_file)?;
+	assert_eq!(
+		runs.lines().count(),
+		3,
+		"Should run once, and then, restart max_restarts times"
+	);
+	assert_eq!(exit_code, 1);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_capture_success_codes() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let version_file = format!("{dir}/captured_version.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&version_file);
+	let toml = format!(
+		r#"
+[[runners.release]]
+name = "version"
+cmd = "sh"
+args = ["-c", "echo v1.2.3; exit 3"]
+capture = {{ var = "VERSION", regex = '^v(.*)' }}
+success_codes = [0, 3]
+
+[[runners.release]]
+name = "write"
+cmd = "sh"
+args = ["-c", "echo $VERSION > {version_file}"]
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("release").ok_or("Should have release runners")?;
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 0);
+	assert_eq!(fs::read_to_string(&version_file)?.trim(), "1.2.3");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_capture_timeout() -> Result<()> {
+	// -- Setup & Fixtures
+	let toml = r#"
+[[runners.slow]]
+name = "capture"
+cmd = "sh"
+args = ["-c", "sleep 100"]
+capture = "NEVER"
+timeout = "200ms"
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+	let runners = config.get_runners_for_ref("slow").ok_or("Should have slow runners")?;
+	let started = Instant::now();
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_ne!(exit_code, 0);
+	assert!(
+		started.elapsed() < Duration::from_secs(2),
+		"Capture should be terminated on its timeout"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_ready_when_rendered() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners/ready_when";
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(format!("{dir}/ready_a.txt"));
+	// Note: `{{DIR}}` from the session vars, `{{flavor}}` from the matrix.
+	let toml = r#"
+[[runners.dev]]
+name = "server"
+cmd = "sh"
+args = ["-c", "touch {{DIR}}/ready_{{flavor}}.txt; sleep 1"]
+concurrent = true
+matrix = { flavor = ["a"] }
+ready_when = { file = "{{DIR}}/ready_{{flavor}}.txt", timeout = "2s" }
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+	let vars = Vars::from([("DIR".to_string(), dir.to_string())]);
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, vars, &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 0, "Should be ready from the rendered file probe");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_logs_default() -> Result<()> {
+	// -- Setup & Fixtures
+	let logs_dir = "target/tests/run_runners/logs_default";
+	let _ = fs::remove_dir_all(logs_dir);
+	let toml = format!(
+		r#"
+[logs]
+dir = "{logs_dir}"
+
+[[runners.dev]]
+name = "build"
+cmd = "echo"
+args = ["building"]
+
+[[runners.dev]]
+name = "server"
+cmd = "echo"
+args = ["serving"]
+concurrent = true
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+
+	// -- Exec
+	run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	let server_log = fs::read_to_string(format!("{logs_dir}/dev/server.log"))?;
+	assert!(
+		server_log.contains("serving"),
+		"Concurrent runners should be logged by default"
+	);
+	assert!(
+		!Path::new(&format!("{logs_dir}/dev/build.log")).exists(),
+		"Sequential runners should not be logged by default"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_logs_shared_by_batches() -> Result<()> {
+	// -- Setup & Fixtures
+	let logs_dir = "target/tests/run_runners/logs_shared";
+	let _ = fs::remove_dir_all(logs_dir);
+	let toml = format!(
+		r#"
+[logs]
+dir = "{logs_dir}"
+
+[[runners.dev]]
+name = "each"
+cmd = "echo"
+args = ["item {{{{item}}}}"]
+for_each = {{ cmd = "printf 'a\nb\nc\n'" }}
+parallel = 3
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+
+	// -- Exec
+	run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	let log = fs::read_to_string(format!("{logs_dir}/dev/each.log"))?;
+	let headers = log.lines().filter(|line| line.starts_with("==== ")).count();
+	assert_eq!(headers, 1, "Should open the log once for all of the batches");
+	for item in ["a", "b", "c"] {
+		assert!(
+			log.contains(&format!("item {item}")),
+			"Should log the batch of '{item}'"
+		);
+	}
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_on_exit_end_all_exit_code() -> Result<()> {
+	// -- Setup & Fixtures
+	// Note: With `continue_on_error`, only the `end_all` gives its exit code to the session.
+	let toml = r#"
+[[runners.dev]]
+name = "watch"
+cmd = "sleep"
+args = ["100"]
+concurrent = true
+
+[[runners.dev]]
+name = "app"
+cmd = "sh"
+args = ["-c", "sleep 0.2; exit 3"]
+concurrent = true
+continue_on_error = true
+on_exit = { failure = "end_all" }
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+	let started = Instant::now();
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 3, "Should exit with the code of the runner ending all");
+	assert!(
+		started.elapsed() < Duration::from_secs(5),
+		"Should stop 'watch' when 'app' ends all"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_concurrent_output_checked_before_exit() -> Result<()> {
+	// -- Setup & Fixtures
+	// Note: The lines printed right before exiting must still be checked.
+	let toml = r#"
+[[runners.dev]]
+name = "failing"
+cmd = "sh"
+args = ["-c", "sleep 0.1; echo ERROR at the end"]
+concurrent = true
+fail_on_output = "ERROR"
+
+[[runners.dev]]
+name = "expecting"
+cmd = "sh"
+args = ["-c", "sleep 0.1; echo status ok"]
+concurrent = true
+expect_output = "status ok"
+"#;
+	let config: Config = parse_awesome_toml(toml)?;
+
+	// -- Exec
+	let failing = config.get_runners_for_ref("dev.failing").ok_or("Should have dev.failing")?;
+	let failing_code = run_runners(&config, failing, None, Vars::new(), &RunOptions::default()).await?;
+	let expecting = config.get_runners_for_ref("dev.expecting").ok_or("Should have dev.expecting")?;
+	let expecting_code = run_runners(&config, expecting, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(failing_code, 1, "Should fail on the last output line");
+	assert_eq!(expecting_code, 0, "Should find the expected last output line");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_mutex_no_overlap() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let locked_file = format!("{dir}/mutex_locked.txt");
+	let free_file = format!("{dir}/mutex_free.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&locked_file);
+	let _ = fs::remove_file(&free_file);
+	let toml = format!(
+		r#"
+[[runners.locked]]
+name = "job"
+cmd = "sh"
+args = ["-c", "echo start >> {locked_file}; sleep 0.2; echo end >> {locked_file}"]
+matrix = {{ n = [1, 2, 3] }}
+mutex = ["shared"]
+parallel = 3
+
+[[runners.free]]
+name = "job"
+cmd = "sh"
+args = ["-c", "echo start >> {free_file}; sleep 0.3; echo end >> {free_file}"]
+matrix = {{ n = [1, 2, 3] }}
+parallel = 3
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+
+	// -- Exec
+	let locked = config.get_runners_for_ref("locked").ok_or("Should have locked runners")?;
+	run_runners(&config, locked, None, Vars::new(), &RunOptions::default()).await?;
+	let free = config.get_runners_for_ref("free").ok_or("Should have free runners")?;
+	run_runners(&config, free, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	let locked_log = fs::read_to_string(&locked_file)?;
+	let locked_lines: Vec<&str> = locked_log.lines().collect();
+	assert_eq!(
+		locked_lines,
+		["start", "end", "start", "end", "start", "end"],
+		"Entries sharing a mutex should never overlap"
+	);
+	let free_log = fs::read_to_string(&free_file)?;
+	let free_lines: Vec<&str> = free_log.lines().collect();
+	assert_eq!(
+		free_lines,
+		["start", "start", "start", "end", "end", "end"],
+		"Entries without a mutex should run in parallel"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_concurrent_output_last_line() -> Result<()> {
+	// -- Setup & Fixtures
+	let logs_dir = "target/tests/run_runners/logs_last_line";
+	let _ = fs::remove_dir_all(logs_dir);
+	let toml = format!(
+		r#"
+[logs]
+dir = "{logs_dir}"
+
+[[runners.dev]]
+name = "server"
+cmd = "printf"
+args = ["first line\nlast line"]
+concurrent = true
+expect_output = "^last line$"
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 0, "Should match the last line (without newline)");
+	let log = fs::read_to_string(format!("{logs_dir}/dev/server.log"))?;
+	let lines: Vec<&str> = log.lines().filter(|line| !line.starts_with("==== ")).collect();
+	assert_eq!(lines, ["first line", "last line"]);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_on_exit_run_and_fail_session() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let notified_file = format!("{dir}/on_exit_notified.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&notified_file);
+	let toml = format!(
+		r#"
+[[runners.dev]]
+name = "app"
+cmd = "sh"
+args = ["-c", "exit 2"]
+concurrent = true
+on_exit = {{ failure = {{ run = "ops.notify" }} }}
+
+[[runners.ci]]
+name = "watch"
+cmd = "sleep"
+args = ["100"]
+concurrent = true
+
+[[runners.ci]]
+name = "check"
+cmd = "sh"
+args = ["-c", "sleep 0.2"]
+concurrent = true
+on_exit = {{ success = "fail_session" }}
+
+[[runners.ops]]
+name = "notify"
+cmd = "sh"
+args = ["-c", "echo app failed > {notified_file}"]
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+
+	// -- Exec
+	let dev = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+	let dev_code = run_runners(&config, dev, None, Vars::new(), &RunOptions::default()).await?;
+	let ci = config.get_runners_for_ref("ci").ok_or("Should have ci runners")?;
+	let started = Instant::now();
+	let ci_code = run_runners(&config, ci, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(dev_code, 2);
+	assert_eq!(fs::read_to_string(&notified_file)?.trim(), "app failed");
+	assert_eq!(ci_code, 1, "fail_session on success should still fail");
+	assert!(
+		started.elapsed() < Duration::from_secs(5),
+		"Should stop 'watch' when 'check' fails the session"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_timeout_exit_code() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let after_file = format!("{dir}/timeout_after.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&after_file);
+	let toml = format!(
+		r#"
+[[runners.test]]
+name = "slow"
+cmd = "sleep"
+args = ["100"]
+timeout = "200ms"
+
+[[runners.test]]
+name = "after"
+cmd = "sh"
+args = ["-c", "touch {after_file}"]
+
+[[runners.watch]]
+name = "server"
+cmd = "sleep"
+args = ["100"]
+concurrent = true
+timeout = 200
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let started = Instant::now();
+
+	// -- Exec
+	let test = config.get_runners_for_ref("test").ok_or("Should have test runners")?;
+	let test_code = run_runners(&config, test, None, Vars::new(), &RunOptions::default()).await?;
+	let watch = config.get_runners_for_ref("watch").ok_or("Should have watch runners")?;
+	let watch_code = run_runners(&config, watch, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	// Note: The summary only gives 124 for the runners with a `Timeout` status.
+	assert_eq!(test_code, 124, "Sequential runner timeout should exit with 124");
+	assert!(
+		!Path::new(&after_file).exists(),
+		"Should not run the next runner after a timeout"
+	);
+	assert_eq!(watch_code, 124, "Concurrent runner timeout should exit with 124");
+	assert!(
+		started.elapsed() < Duration::from_secs(3),
+		"Runners should be stopped on their timeout"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_stdin_from() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let count_file = format!("{dir}/stdin_from_count.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&count_file);
+	let toml = format!(
+		r#"
+[[runners.data]]
+name = "list"
+cmd = "printf"
+args = ["active a\ninactive b\nactive c\n"]
+
+[[runners.data]]
+name = "count"
+cmd = "sh"
+args = ["-c", "grep -c '^active' > {count_file}"]
+stdin = {{ from = "data.list" }}
+
+[[runners.orphan]]
+name = "count"
+cmd = "cat"
+stdin = {{ from = "data.list" }}
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+
+	// -- Exec
+	let data = config.get_runners_for_ref("data").ok_or("Should have data runners")?;
+	let data_code = run_runners(&config, data, None, Vars::new(), &RunOptions::default()).await?;
+	let orphan = config.get_runners_for_ref("orphan").ok_or("Should have orphan runners")?;
+	let orphan_code = run_runners(&config, orphan, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(data_code, 0);
+	assert_eq!(fs::read_to_string(&count_file)?.trim(), "2");
+	assert_ne!(orphan_code, 0, "Should fail when the runner read from did not run");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_for_each_batch() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let batches_file = format!("{dir}/for_each_batches.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&batches_file);
+	let toml = format!(
+		r#"
+[[runners.gen]]
+name = "protos"
+cmd = "sh"
+args = ["-c", "echo \"$@\" >> {batches_file}", "sh", "{{{{item}}}}"]
+for_each = {{ cmd = "printf 'a\nb\nc\nd\ne\n'", batch = 2 }}
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("gen").ok_or("Should have gen runners")?;
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 0);
+	let batches = fs::read_to_string(&batches_file)?;
+	let batches: Vec<&str> = batches.lines().collect();
+	assert_eq!(
+		batches,
+		["a b", "c d", "e"],
+		"Should repeat the {{{{item}}}} arg for each item of a batch"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_hooks_order_on_failure() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let order_file = format!("{dir}/hooks_order.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&order_file);
+	let step = |name: &str| {
+		format!("[[runner]]\nname = \"{name}\"\ncmd = \"sh\"\nargs = [\"-c\", \"echo {name} >> {order_file}\"]\n")
+	};
+	let toml = format!(
+		r#"
+[hooks.build]
+before = ["up"]
+after = ["report"]
+on_failure = ["notify"]
+finally = ["down"]
+
+[[runners.build]]
+name = "compile"
+cmd = "sh"
+args = ["-c", "echo compile >> {order_file}; exit 3"]
+
+[[runners.build]]
+name = "package"
+cmd = "sh"
+args = ["-c", "echo package >> {order_file}"]
+
+{}
+{}
+{}
+{}
+"#,
+		step("up"),
+		step("report"),
+		step("notify"),
+		step("down")
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("build").ok_or("Should have build runners")?;
+	let hooks = config.get_hooks_for_ref("build");
+
+	// -- Exec
+	let exit_code = run_runners(&config, runners, hooks, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(exit_code, 3, "Should exit with the code of the failed runner");
+	let order = fs::read_to_string(&order_file)?;
+	let order: Vec<&str> = order.lines().collect();
+	assert_eq!(order, ["up", "compile", "notify", "down"]);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_wait_for_file() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners/wait_for";
+	let _ = fs::remove_dir_all(dir);
+	fs::create_dir_all(dir)?;
+	let toml = format!(
+		r#"
+[[runners.dev]]
+name = "build"
+cmd = "sh"
+args = ["-c", "sleep 0.3; touch {dir}/ready"]
+concurrent = true
+
+[[runners.dev]]
+name = "wait_build"
+wait_for = {{ file = "{dir}/ready", interval = 50 }}
+
+[[runners.dev]]
+name = "serve"
+cmd = "sh"
+args = ["-c", "touch {dir}/served"]
+
+[[runners.stuck]]
+name = "wait_never"
+wait_for = {{ file = "{dir}/never", timeout = "300ms", interval = 50 }}
+
+[[runners.stuck]]
+name = "serve"
+cmd = "sh"
+args = ["-c", "touch {dir}/stuck_served"]
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+
+	// -- Exec
+	let dev = config.get_runners_for_ref("dev").ok_or("Should have dev runners")?;
+	let dev_code = run_runners(&config, dev, None, Vars::new(), &RunOptions::default()).await?;
+	let stuck = config.get_runners_for_ref("stuck").ok_or("Should have stuck runners")?;
+	let started = Instant::now();
+	let stuck_code = run_runners(&config, stuck, None, Vars::new(), &RunOptions::default()).await?;
+
+	// -- Check
+	assert_eq!(dev_code, 0);
+	assert!(
+		Path::new(dir).join("served").exists(),
+		"Should run 'serve' once the file exists"
+	);
+	assert_eq!(stuck_code, 1, "Should fail on the wait_for timeout");
+	assert!(started.elapsed() < Duration::from_secs(2));
+	assert!(
+		!Path::new(dir).join("stuck_served").exists(),
+		"Should not run the next runner after a wait_for timeout"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_confirm_yes_and_no_tty() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners/confirm";
+	let _ = fs::remove_dir_all(dir);
+	fs::create_dir_all(dir)?;
+	let toml = format!(
+		r#"
+[[runners.db]]
+name = "reset"
+confirm = "Reset local database?"
+cmd = "sh"
+args = ["-c", "touch {dir}/reset"]
+
+[[runners.db]]
+name = "seed"
+cmd = "sh"
+args = ["-c", "touch {dir}/seed"]
+
+[[runners.prod]]
+name = "deploy"
+confirm = "Deploy to prod?"
+confirm_default = "abort"
+cmd = "sh"
+args = ["-c", "touch {dir}/deploy"]
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let yes = RunOptions {
+		yes: true,
+		..Default::default()
+	};
+
+	// -- Exec & Check
+	let db = config.get_runners_for_ref("db").ok_or("Should have db runners")?;
+	assert_eq!(run_runners(&config, db, None, Vars::new(), &yes).await?, 0);
+	assert!(Path::new(dir).join("reset").exists(), "--yes should answer yes");
+
+	// Note: Only checkable when the tests do not run with a terminal stdin.
+	if !io::stdin().is_terminal() {
+		fs::remove_file(Path::new(dir).join("reset"))?;
+		fs::remove_file(Path::new(dir).join("seed"))?;
+		let db = config.get_runners_for_ref("db").ok_or("Should have db runners")?;
+		assert_eq!(
+			run_runners(&config, db, None, Vars::new(), &RunOptions::default()).await?,
+			0
+		);
+		assert!(!Path::new(dir).join("reset").exists(), "No tty should skip by default");
+		assert!(
+			Path::new(dir).join("seed").exists(),
+			"Should still run the next runners"
+		);
+
+		let prod = config.get_runners_for_ref("prod").ok_or("Should have prod runners")?;
+		let prod_code = run_runners(&config, prod, None, Vars::new(), &RunOptions::default()).await?;
+		assert_eq!(prod_code, 1, "No tty should fail with confirm_default = \"abort\"");
+		assert!(!Path::new(dir).join("deploy").exists());
+	}
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_runners_inputs_non_interactive() -> Result<()> {
+	// -- Setup & Fixtures
+	let dir = "target/tests/run_runners";
+	let gen_file = format!("{dir}/inputs_gen.txt");
+	fs::create_dir_all(dir)?;
+	let _ = fs::remove_file(&gen_file);
+	let toml = format!(
+		r#"
+[[runner]]
+name = "gen"
+inputs = [
+	{{ name = "component", default = "Button" }},
+	{{ name = "env", choices = ["dev", "staging"] }},
+]
+cmd = "sh"
+args = ["-c", "echo {{{{component}}}} $env > {gen_file}"]
+"#
+	);
+	let config: Config = parse_awesome_toml(&toml)?;
+	let runners = config.get_runners_for_ref("gen").ok_or("Should have gen runners")?;
+	let with_env = RunOptions {
+		inputs: vec![("env".to_string(), "staging".to_string())],
+		..Default::default()
+	};
+
+	// -- Exec
+	let missing_res = input_vars(&runners, &RunOptions::default(), false);
+	let vars = input_vars(&runners, &with_env, false)?;
+	let exit_code = run_runners(&config, runners, None, vars, &with_env).await?;
+
+	// -- Check
+	let err = missing_res.err().ok_or("Should fail on the missing required input")?;
+	assert!(
+		err.to_string().contains("env"),
+		"Should name the missing input. Was: {err}"
+	);
+	assert_eq!(exit_code, 0);
+	assert_eq!(fs::read_to_string(&gen_file)?.trim(), "Button staging");
+
+	Ok(())
+}
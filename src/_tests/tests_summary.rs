@@ -0,0 +1,83 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::{format_duration, RunRecord, RunStatus, Summary};
+use crate::config::HookKind;
+use std::time::Duration;
+
+fn record(name: &str, status: RunStatus, continue_on_error: bool) -> RunRecord {
+	RunRecord {
+		name: name.to_string(),
+		status,
+		continue_on_error,
+		attempts: 1,
+		hook: None,
+		confirm: None,
+		started: None,
+		duration: Duration::ZERO,
+	}
+}
+
+fn failed(code: i32) -> RunStatus {
+	RunStatus::Failed {
+		code: Some(code),
+		reason: format!("exit status: {code}"),
+	}
+}
+
+#[test]
+fn test_summary_exit_code() -> Result<()> {
+	// -- Setup & Fixtures
+	let mut summary = Summary::default();
+	summary.records.push(record("build.fmt", RunStatus::Ok, false));
+	summary.records.push(record("build.lint", failed(3), true));
+	summary.records.push(record("build.test", failed(101), false));
+	summary.records.push(record("build.deploy", RunStatus::Pending, false));
+
+	// -- Exec & Check
+	// continue_on_error failures do not fail the session
+	assert_eq!(summary.exit_code(), 101);
+
+	summary.finish();
+	assert_eq!(summary.records[3].status, RunStatus::Skipped("not started".to_string()));
+
+	summary.set_exit_code(130);
+	assert_eq!(summary.exit_code(), 130);
+
+	let mut summary = Summary::default();
+	summary.records.push(record("build.lint", failed(3), true));
+	assert_eq!(summary.exit_code(), 0);
+
+	Ok(())
+}
+
+#[test]
+fn test_summary_exit_code_hooks() -> Result<()> {
+	// -- Setup & Fixtures
+	let mut summary = Summary::default();
+	summary.records.push(record("dev.server", RunStatus::Ok, false));
+	let mut finally = record("dev.down", failed(2), false);
+	finally.hook = Some(HookKind::Finally);
+	summary.records.push(finally);
+
+	// -- Exec & Check
+	// finally (and on_failure) hook failures are only reported
+	assert_eq!(summary.exit_code(), 0);
+
+	let mut before = record("dev.up", failed(4), false);
+	before.hook = Some(HookKind::Before);
+	summary.records.push(before);
+	assert_eq!(summary.exit_code(), 4);
+
+	Ok(())
+}
+
+#[test]
+fn test_summary_format_duration() -> Result<()> {
+	// -- Exec & Check
+	assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+	assert_eq!(format_duration(Duration::from_millis(3240)), "3.2s");
+	assert_eq!(format_duration(Duration::from_millis(125_000)), "2m05s");
+
+	Ok(())
+}
@@ -0,0 +1,112 @@
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For early dev.
+
+use super::supervise;
+use crate::config::{parse_awesome_toml, Config};
+#[cfg(unix)]
+use crate::test_support::spawn_sh;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout};
+
+const RUNNERS_TOML: &str = r#"
+[[runners.dev]]
+name = "quick"
+cmd = "sh"
+concurrent = true
+
+[[runners.dev]]
+name = "sleeper"
+cmd = "sh"
+concurrent = true
+stop_timeout = 2000
+"#;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_supervisor_exit_event() -> Result<()> {
+	// -- Setup & Fixtures
+	let config: Config = parse_awesome_toml(RUNNERS_TOML)?;
+	let runner = config.get_grouped_runner("dev", "quick").ok_or("Should have quick")?;
+	let (exit_tx, mut exit_rx) = mpsc::unbounded_channel();
+	let started = Instant::now();
+
+	// -- Exec
+	let _supervised = supervise(3, runner, spawn_sh("exit 7")?, Vec::new(), exit_tx);
+	let event = timeout(Duration::from_secs(2), exit_rx.recv())
+		.await?
+		.ok_or("Should have an exit event")?;
+
+	// -- Check
+	assert_eq!(event.idx, 3);
+	assert_eq!(event.status?.code(), Some(7));
+	assert!(!event.timed_out);
+	assert!(
+		started.elapsed() < Duration::from_secs(1),
+		"Exit event should be prompt"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_supervisor_stop() -> Result<()> {
+	// -- Setup & Fixtures
+	let config: Config = parse_awesome_toml(RUNNERS_TOML)?;
+	let runner = config.get_grouped_runner("dev", "sleeper").ok_or("Should have sleeper")?;
+	let (exit_tx, mut exit_rx) = mpsc::unbounded_channel();
+	let mut supervised = supervise(0, runner, spawn_sh("sleep 100")?, Vec::new(), exit_tx);
+	let started = Instant::now();
+
+	// -- Exec
+	supervised.stop();
+	let event = timeout(Duration::from_secs(5), exit_rx.recv())
+		.await?
+		.ok_or("Should have an exit event")?;
+
+	// -- Check
+	assert!(!event.status?.success(), "Stopped child should not exit successfully");
+	assert!(!event.timed_out);
+	assert!(
+		started.elapsed() < Duration::from_secs(2),
+		"Child should stop on the stop_signal, before stop_timeout"
+	);
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_supervisor_exit_event_after_pumps() -> Result<()> {
+	// -- Setup & Fixtures
+	let config: Config = parse_awesome_toml(RUNNERS_TOML)?;
+	let runner = config.get_grouped_runner("dev", "quick").ok_or("Should have quick")?;
+	let (exit_tx, mut exit_rx) = mpsc::unbounded_channel();
+	let pumped = Arc::new(AtomicBool::new(false));
+	// Note: A slow output pump, still processing the last lines when the child exits.
+	let pump = tokio::spawn({
+		let pumped = pumped.clone();
+		async move {
+			sleep(Duration::from_millis(200)).await;
+			pumped.store(true, Ordering::SeqCst);
+		}
+	});
+
+	// -- Exec
+	let _supervised = supervise(0, runner, spawn_sh("exit 0")?, vec![pump], exit_tx);
+	let event = timeout(Duration::from_secs(2), exit_rx.recv())
+		.await?
+		.ok_or("Should have an exit event")?;
+
+	// -- Check
+	assert!(event.status?.success());
+	assert!(
+		pumped.load(Ordering::SeqCst),
+		"Exit event should be sent once the output got processed"
+	);
+
+	Ok(())
+}
@@ -0,0 +1,168 @@
+//! The built-in runner actions (file operations), run without an external program.
+
+use crate::config::Action;
+use crate::{Error, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Run the (rendered) action. Relative paths are from `base_dir`, and the deleted paths (rm, move source)
+/// must be inside `root_dir` (see `check_safe_to_delete`).
+pub fn run_action(action: &Action, base_dir: &Path, root_dir: &Path) -> Result<()> {
+	println!("> action: {action}");
+	let failed = |ex: io::Error| Error::ActionFailed(action.to_string(), ex.to_string());
+
+	match action {
+		Action::Rm(paths) => {
+			for raw in paths {
+				let path = base_dir.join(raw);
+				// Note: Like `rm -f`, a missing path is not an error.
+				let Ok(metadata) = fs::symlink_metadata(&path) else {
+					continue;
+				};
+				check_safe_to_delete(root_dir, raw, &path)?;
+				if metadata.is_dir() {
+					fs::remove_dir_all(&path).map_err(failed)?;
+				} else {
+					fs::remove_file(&path).map_err(failed)?;
+				}
+			}
+		}
+		Action::Mkdir(paths) => {
+			for raw in paths {
+				fs::create_dir_all(base_dir.join(raw)).map_err(failed)?;
+			}
+		}
+		Action::Touch(paths) => {
+			for raw in paths {
+				let path = base_dir.join(raw);
+				create_parent_dir(&path).map_err(failed)?;
+				let file = OpenOptions::new().create(true).append(true).open(&path).map_err(failed)?;
+				file.set_modified(SystemTime::now()).map_err(failed)?;
+			}
+		}
+		Action::Copy { from, to } => {
+			let from = base_dir.join(from);
+			let to = target_path(&from, &base_dir.join(to));
+			create_parent_dir(&to).map_err(failed)?;
+			copy_all(&from, &to).map_err(failed)?;
+		}
+		Action::Move { from: raw, to } => {
+			let from = base_dir.join(raw);
+			check_safe_to_delete(root_dir, raw, &from)?;
+			let to = target_path(&from, &base_dir.join(to));
+			create_parent_dir(&to).map_err(failed)?;
+			// Note: Rename fails across devices, then, copy and delete.
+			if fs::rename(&from, &to).is_err() {
+				copy_all(&from, &to).map_err(failed)?;
+				match from.is_dir() {
+					true => fs::remove_dir_all(&from).map_err(failed)?,
+					false => fs::remove_file(&from).map_err(failed)?,
+				}
+			}
+		}
+		Action::WriteFile { path, content, append } => {
+			let path = base_dir.join(path);
+			create_parent_dir(&path).map_err(failed)?;
+			let mut file = OpenOptions::new()
+				.create(true)
+				.write(true)
+				.append(*append)
+				.truncate(!*append)
+				.open(&path)
+				.map_err(failed)?;
+			file.write_all(content.as_bytes()).map_err(failed)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Check that a path is safe to delete. Refuses `/`, `~` (and `~/...`), the root dir itself,
+/// and the paths outside of the root dir.
+/// Note: The last component is not resolved, so a symlink (inside the root dir) can be deleted
+///       even if it points outside of it.
+pub fn check_safe_to_delete(root_dir: &Path, raw: &str, path: &Path) -> Result<()> {
+	let not_safe = || Error::PathNotSafeToDelete(raw.to_string());
+
+	let raw = raw.trim();
+	if raw.is_empty() || raw == "/" || raw.starts_with('~') {
+		return Err(not_safe());
+	}
+
+	let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+		return Err(not_safe());
+	};
+	let parent = if parent.as_os_str().is_empty() {
+		Path::new(".")
+	} else {
+		parent
+	};
+	let root_dir = root_dir.canonicalize()?;
+	let path = parent.canonicalize()?.join(file_name);
+	if path == root_dir || !path.starts_with(&root_dir) {
+		return Err(not_safe());
+	}
+
+	Ok(())
+}
+
+/// When `to` is an existing directory, the path in it, with the `from` file name.
+fn target_path(from: &Path, to: &Path) -> PathBuf {
+	match (to.is_dir(), from.file_name()) {
+		(true, Some(file_name)) => to.join(file_name),
+		_ => to.to_path_buf(),
+	}
+}
+
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+	match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+		Some(parent) => fs::create_dir_all(parent),
+		None => Ok(()),
+	}
+}
+
+/// Copy a file, or a directory recursively (refusing to copy a path into itself, e.g., `a` to `a/b`).
+/// Note: The parent dir of `to` must exist (to resolve it).
+fn copy_all(from: &Path, to: &Path) -> io::Result<()> {
+	let from_path = from.canonicalize()?;
+	let to_path = match (to.canonicalize(), to.parent(), to.file_name()) {
+		(Ok(to_path), _, _) => to_path,
+		(Err(_), Some(parent), Some(file_name)) => {
+			let parent = if parent.as_os_str().is_empty() {
+				Path::new(".")
+			} else {
+				parent
+			};
+			parent.canonicalize()?.join(file_name)
+		}
+		(Err(ex), _, _) => return Err(ex),
+	};
+	if to_path.starts_with(&from_path) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("cannot copy '{}' into itself ('{}')", from.display(), to.display()),
+		));
+	}
+	copy_tree(from, to)
+}
+
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+	if from.is_dir() {
+		fs::create_dir_all(to)?;
+		for entry in fs::read_dir(from)? {
+			let entry = entry?;
+			copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+		}
+	} else {
+		fs::copy(from, to)?;
+	}
+	Ok(())
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_actions.rs"]
+mod tests;
+// endregion: --- Tests
@@ -1,16 +1,112 @@
-use clap::{crate_version, Arg, Command};
+use crate::completions::{complete_run_ref, SHELLS};
+use clap::builder::PossibleValuesParser;
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+use clap_complete::ArgValueCompleter;
 
 pub const VERSION: &str = crate_version!();
 
 pub fn app_cmd() -> Command {
-	Command::new("awesome").version(VERSION).about("Awesome CLI Runner").arg(
-		Arg::new("INPUT")
-			.help(
-				r#"- `group_name` to execute all commands in a group (from `[[runners.group_name]]`).
+	Command::new("awesome")
+		.version(VERSION)
+		.about("Awesome CLI Runner")
+		.args_conflicts_with_subcommands(true)
+		.arg(
+			Arg::new("INPUT")
+				.help(
+					r#"- `group_name` to execute all commands in a group (from `[[runners.group_name]]`).
 - `group_name.runner_name` to execute a specific command from a group (found in `[[runners.group_name]]`, then search by name).
 - `solo_runner_name` from the `[[runner]]` table (matched by the name property)."#,
-			)
-			.required(true)
-			.index(1),
-	)
+				)
+				.index(1)
+				.add(ArgValueCompleter::new(complete_run_ref)),
+		)
+		.arg(
+			Arg::new("ARGS")
+				.help("The values of the runner `inputs`, in their order (the ones not given with `--input`)")
+				.index(2)
+				.num_args(1..),
+		)
+		.arg(
+			Arg::new("input")
+				.long("input")
+				.value_name("NAME=VALUE")
+				.value_parser(parse_input)
+				.action(ArgAction::Append)
+				.help("The value of a runner input (can be repeated)"),
+		)
+		.arg(
+			Arg::new("keep-going")
+				.short('k')
+				.long("keep-going")
+				.action(ArgAction::SetTrue)
+				.help("Keep running the next runners when a runner fails (like `continue_on_error` on all runners)"),
+		)
+		.arg(
+			Arg::new("timeout")
+				.long("timeout")
+				.value_name("DURATION")
+				.value_parser(humantime::parse_duration)
+				.help("Max duration of the whole session (e.g., `10m`), the runners still running get terminated"),
+		)
+		.arg(
+			Arg::new("yes")
+				.short('y')
+				.long("yes")
+				.action(ArgAction::SetTrue)
+				.help("Answer yes to the runner `confirm` questions (e.g., in CI)"),
+		)
+		.subcommand(sub_logs())
+		.subcommand(sub_completions())
+}
+
+/// `name=value` of `--input`.
+fn parse_input(val: &str) -> Result<(String, String), String> {
+	match val.split_once('=') {
+		Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.to_string())),
+		_ => Err(format!("'{val}' should be NAME=VALUE")),
+	}
+}
+
+fn sub_logs() -> Command {
+	Command::new("logs")
+		.about("Print the log files of a group or runner (from `.awesome/logs/`)")
+		.arg(
+			Arg::new("REF")
+				.help("`group_name`, `group_name.runner_name`, or `solo_runner_name`")
+				.required(true)
+				.index(1)
+				.add(ArgValueCompleter::new(complete_run_ref)),
+		)
+		.arg(
+			Arg::new("follow")
+				.short('f')
+				.long("follow")
+				.action(ArgAction::SetTrue)
+				.help("Keep printing the new lines"),
+		)
+		.arg(
+			Arg::new("tail")
+				.short('n')
+				.long("tail")
+				.value_parser(value_parser!(usize))
+				.help("Only print the last N lines of each log"),
+		)
+		.arg(
+			Arg::new("grep")
+				.long("grep")
+				.value_name("REGEX")
+				.help("Only print the lines matching the regex"),
+		)
+}
+
+fn sub_completions() -> Command {
+	Command::new("completions")
+		.about("Print the shell completion script, e.g., `source <(awesome completions bash)` in `~/.bashrc`")
+		.arg(
+			Arg::new("SHELL")
+				.help("The shell of the completion script")
+				.required(true)
+				.value_parser(PossibleValuesParser::new(SHELLS))
+				.index(1),
+		)
 }
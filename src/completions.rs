@@ -0,0 +1,60 @@
+//! Shell completions, with the run refs completed dynamically from the `Awesome.toml` of the current dir.
+//!
+//! The registration script (`awesome completions bash`) calls back into `awesome` (with `COMPLETE=bash`)
+//! on each completion, handled by `clap_complete::CompleteEnv` at the start of `main`.
+
+use crate::config::{find_and_parse_awesome_toml, Config};
+use crate::picker::{pick_items, PickItem};
+use crate::{Error, Result};
+use clap_complete::env::Shells;
+use clap_complete::CompletionCandidate;
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+
+/// The env var activating the completion (see `clap_complete::CompleteEnv`).
+pub const COMPLETE_VAR: &str = "COMPLETE";
+/// The supported shells of `awesome completions <SHELL>`.
+pub const SHELLS: [&str; 4] = ["bash", "zsh", "fish", "elvish"];
+const BIN_NAME: &str = "awesome";
+
+/// Print the registration script of the shell (to be sourced by the shell config).
+pub fn print_completions(shell: &str) -> Result<()> {
+	let shells = Shells::builtins();
+	let completer = shells
+		.completer(shell)
+		.ok_or_else(|| Error::ShellNotSupported(shell.to_string()))?;
+	completer.write_registration(COMPLETE_VAR, BIN_NAME, BIN_NAME, BIN_NAME, &mut io::stdout())?;
+	Ok(())
+}
+
+/// The run refs starting with `current`, from the `Awesome.toml` of the current dir (the one `awesome` runs).
+/// The groups and solo runners, or, after a `.`, the runners of the group (e.g., `build.<TAB>`).
+/// Note: Not from the parent dirs, as `awesome` itself only reads `./Awesome.toml`.
+pub fn complete_run_ref(current: &OsStr) -> Vec<CompletionCandidate> {
+	let Ok(config) = find_and_parse_awesome_toml(Path::new("./")) else {
+		return Vec::new();
+	};
+	matching_items(&config, &current.to_string_lossy())
+		.into_iter()
+		.map(|item| {
+			let help = (!item.description.is_empty()).then(|| item.description.into());
+			CompletionCandidate::new(item.run_ref).help(help)
+		})
+		.collect()
+}
+
+/// The picker items starting with `current`, without the grouped runners, unless after a `.`.
+fn matching_items(config: &Config, current: &str) -> Vec<PickItem> {
+	let in_group = current.contains('.');
+	pick_items(config)
+		.into_iter()
+		.filter(|item| item.run_ref.contains('.') == in_group && item.run_ref.starts_with(current))
+		.collect()
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_completions.rs"]
+mod tests;
+// endregion: --- Tests
@@ -0,0 +1,147 @@
+use crate::utils::{render_template, Vars};
+use serde_derive::Deserialize;
+use std::fmt;
+
+/// The built-in runner actions (`action = "..."`), run without an external program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+	Rm,
+	Mkdir,
+	Copy,
+	Move,
+	Touch,
+	WriteFile,
+}
+
+/// The parameters of the built-in actions (in the runner table), with `{{NAME}}` vars.
+#[derive(Debug, Default, Deserialize)]
+pub struct ActionParams {
+	/// For `rm`, `mkdir`, `touch`, and `write_file`.
+	pub path: Option<String>,
+	/// For `rm`, `mkdir`, and `touch` (in addition to `path`).
+	#[serde(default)]
+	pub paths: Vec<String>,
+	/// For `copy` and `move`.
+	pub from: Option<String>,
+	pub to: Option<String>,
+	/// For `write_file`.
+	pub content: Option<String>,
+	/// For `write_file`, append to the file instead of replacing it.
+	#[serde(default)]
+	pub append: bool,
+}
+
+/// A built-in action with its (required) parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+	/// Remove the files or directories (recursively), the missing ones being ignored.
+	Rm(Vec<String>),
+	/// Create the directories (with their parents).
+	Mkdir(Vec<String>),
+	/// Copy a file or a directory (recursively). When `to` is an existing directory, copy into it.
+	Copy { from: String, to: String },
+	/// Move (rename) a file or a directory. When `to` is an existing directory, move into it.
+	Move { from: String, to: String },
+	/// Create the files if missing, otherwise, update their modified time.
+	Touch(Vec<String>),
+	WriteFile {
+		path: String,
+		content: String,
+		append: bool,
+	},
+}
+
+impl Action {
+	/// Build the action from its kind and parameters. Returns the reason when a parameter is missing.
+	pub fn new(kind: ActionKind, params: &ActionParams) -> core::result::Result<Self, String> {
+		let paths = || -> core::result::Result<Vec<String>, String> {
+			let paths: Vec<String> = params.path.iter().chain(params.paths.iter()).cloned().collect();
+			if paths.is_empty() {
+				Err(format!("'{kind}' requires 'path' or 'paths'"))
+			} else {
+				Ok(paths)
+			}
+		};
+		let from_to = || match (&params.from, &params.to) {
+			(Some(from), Some(to)) => Ok((from.to_string(), to.to_string())),
+			_ => Err(format!("'{kind}' requires 'from' and 'to'")),
+		};
+
+		Ok(match kind {
+			ActionKind::Rm => Action::Rm(paths()?),
+			ActionKind::Mkdir => Action::Mkdir(paths()?),
+			ActionKind::Touch => Action::Touch(paths()?),
+			ActionKind::Copy => {
+				let (from, to) = from_to()?;
+				Action::Copy { from, to }
+			}
+			ActionKind::Move => {
+				let (from, to) = from_to()?;
+				Action::Move { from, to }
+			}
+			ActionKind::WriteFile => match (&params.path, &params.content) {
+				(Some(path), Some(content)) => Action::WriteFile {
+					path: path.to_string(),
+					content: content.to_string(),
+					append: params.append,
+				},
+				_ => return Err(format!("'{kind}' requires 'path' and 'content'")),
+			},
+		})
+	}
+
+	/// The action with the `{{NAME}}` vars replaced in its parameters.
+	pub fn render(&self, vars: &Vars) -> Action {
+		let render_all = |paths: &[String]| paths.iter().map(|p| render_template(p, vars)).collect();
+		match self {
+			Action::Rm(paths) => Action::Rm(render_all(paths)),
+			Action::Mkdir(paths) => Action::Mkdir(render_all(paths)),
+			Action::Touch(paths) => Action::Touch(render_all(paths)),
+			Action::Copy { from, to } => Action::Copy {
+				from: render_template(from, vars),
+				to: render_template(to, vars),
+			},
+			Action::Move { from, to } => Action::Move {
+				from: render_template(from, vars),
+				to: render_template(to, vars),
+			},
+			Action::WriteFile { path, content, append } => Action::WriteFile {
+				path: render_template(path, vars),
+				content: render_template(content, vars),
+				append: *append,
+			},
+		}
+	}
+}
+
+impl fmt::Display for ActionKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let name = match self {
+			ActionKind::Rm => "rm",
+			ActionKind::Mkdir => "mkdir",
+			ActionKind::Copy => "copy",
+			ActionKind::Move => "move",
+			ActionKind::Touch => "touch",
+			ActionKind::WriteFile => "write_file",
+		};
+		write!(f, "{name}")
+	}
+}
+
+/// e.g., `rm target/tmp dist`, `copy assets dist/assets`, `write_file dist/VERSION`
+impl fmt::Display for Action {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Action::Rm(paths) => write!(f, "rm {}", paths.join(" ")),
+			Action::Mkdir(paths) => write!(f, "mkdir {}", paths.join(" ")),
+			Action::Touch(paths) => write!(f, "touch {}", paths.join(" ")),
+			Action::Copy { from, to } => write!(f, "copy {from} {to}"),
+			Action::Move { from, to } => write!(f, "move {from} {to}"),
+			Action::WriteFile { path, append, .. } => match append {
+				true => write!(f, "write_file {path} (append)"),
+				false => write!(f, "write_file {path}"),
+			},
+		}
+	}
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Deserializer};
+use serde_derive::Deserialize;
+use std::time::Duration;
+
+/// A duration property, as a humantime string (e.g., "5m", "1h 30m", "500ms"), or as an integer in ms.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DurationValue {
+	Ms(u64),
+	Text(String),
+}
+
+/// Deserialize a duration property (to be used with `#[serde(deserialize_with = "...")]`).
+pub fn deserialize_duration<'de, D>(deserializer: D) -> core::result::Result<Duration, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	match DurationValue::deserialize(deserializer)? {
+		DurationValue::Ms(ms) => Ok(Duration::from_millis(ms)),
+		DurationValue::Text(text) => humantime::parse_duration(&text)
+			.map_err(|ex| serde::de::Error::custom(format!("invalid duration '{text}'. Cause: {ex}"))),
+	}
+}
+
+/// Deserialize an optional duration property (to be used with `#[serde(default, deserialize_with = "...")]`).
+pub fn deserialize_opt_duration<'de, D>(deserializer: D) -> core::result::Result<Option<Duration>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	deserialize_duration(deserializer).map(Some)
+}
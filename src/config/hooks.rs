@@ -0,0 +1,44 @@
+use serde_derive::Deserialize;
+use std::fmt;
+
+/// The `[hooks.group_name]` tables of the `Awesome.toml`, with the run refs of the hook runners
+/// (e.g., `"dev.db_up"`, `"cleanup"`), run one after the other, to completion.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+	/// Run before the group runners. When one fails, the group runners do not run.
+	pub before: Vec<String>,
+	/// Run after the group runners, when all succeeded.
+	pub after: Vec<String>,
+	/// Run when the session failed (e.g., runner or hook failure, timeout), but not when interrupted.
+	pub on_failure: Vec<String>,
+	/// Always run at the end, even when interrupted by Ctrl-C, or ended by `end_all_on_exit`.
+	pub finally: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+	Before,
+	After,
+	OnFailure,
+	Finally,
+}
+
+impl HookKind {
+	/// The `before` and `after` hook failures fail the session, the others are only reported.
+	pub fn fails_session(&self) -> bool {
+		matches!(self, HookKind::Before | HookKind::After)
+	}
+}
+
+impl fmt::Display for HookKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let name = match self {
+			HookKind::Before => "before",
+			HookKind::After => "after",
+			HookKind::OnFailure => "on_failure",
+			HookKind::Finally => "finally",
+		};
+		write!(f, "{name}")
+	}
+}
@@ -0,0 +1,120 @@
+use crate::exec::prompt;
+use crate::utils::Vars;
+use crate::{Error, Result};
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+
+/// A runner input, e.g., `{ name = "env", prompt = "Environment?", choices = ["dev", "staging"], default = "dev" }`,
+/// available as `{{name}}` (and as an env var) to the runners of the session.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Input {
+	pub name: String,
+	/// The question when prompted (default the input name).
+	pub prompt: Option<String>,
+	/// The value when not given. Without a default, the input is required.
+	pub default: Option<String>,
+	/// When not empty, the only accepted values.
+	#[serde(default)]
+	pub choices: Vec<String>,
+}
+
+impl Input {
+	/// Check the input definition (`runner_name` for the errors).
+	pub(super) fn check(&self, runner_name: &str) -> Result<()> {
+		let invalid = |reason: String| Error::InvalidInputDef(runner_name.to_string(), self.name.to_string(), reason);
+		if self.name.is_empty() {
+			return Err(invalid("'name' should not be empty".to_string()));
+		}
+		if let Some(default) = self.default.as_ref().filter(|d| !self.is_choice(d)) {
+			return Err(invalid(format!("default '{default}' is not one of the choices")));
+		}
+		Ok(())
+	}
+
+	fn is_choice(&self, value: &str) -> bool {
+		self.choices.is_empty() || self.choices.iter().any(|c| c == value)
+	}
+
+	/// Check a given value against the `choices`.
+	fn check_value(&self, value: &str) -> Result<()> {
+		if self.is_choice(value) {
+			Ok(())
+		} else {
+			Err(Error::InputInvalid(
+				self.name.to_string(),
+				format!("'{value}' is not one of {}", self.choices.join(", ")),
+			))
+		}
+	}
+
+	/// Prompt for the value until valid, e.g., `Environment? [dev/staging] (dev): `.
+	fn prompt_value(&self) -> Result<String> {
+		let mut message = self.prompt.as_deref().unwrap_or(&self.name).to_string();
+		if !self.choices.is_empty() {
+			message.push_str(&format!(" [{}]", self.choices.join("/")));
+		}
+		if let Some(default) = &self.default {
+			message.push_str(&format!(" ({default})"));
+		}
+		message.push_str(": ");
+
+		loop {
+			let value = prompt(&message, self.default.as_deref())?;
+			match value.is_empty() {
+				true => println!("Input '{}' is required", self.name),
+				false => match self.check_value(&value) {
+					Ok(()) => return Ok(value),
+					Err(ex) => println!("{ex}"),
+				},
+			}
+		}
+	}
+}
+
+/// Resolve the values of the `inputs` (the first definition of a name wins), from the `--input name=value`
+/// values, then, the positional `args` (in the inputs order, skipping the ones given by name), and then,
+/// prompted when `interactive`, otherwise, their default. Errors when a required input is missing,
+/// a value is not one of the choices, or a value does not match any input.
+pub fn resolve_inputs(
+	inputs: &[&Input],
+	values: &[(String, String)],
+	args: &[String],
+	interactive: bool,
+) -> Result<Vars> {
+	let mut inputs: Vec<&Input> = inputs.to_vec();
+	let mut seen = HashSet::new();
+	inputs.retain(|input| seen.insert(input.name.as_str()));
+
+	// -- The values given by name.
+	let mut vars = Vars::new();
+	for (name, value) in values {
+		let input = inputs
+			.iter()
+			.find(|input| &input.name == name)
+			.ok_or_else(|| Error::InputInvalid(name.to_string(), "not an input of the runners".to_string()))?;
+		input.check_value(value)?;
+		vars.insert(name.to_string(), value.to_string());
+	}
+
+	// -- The positional values.
+	let missing: Vec<&Input> = inputs.into_iter().filter(|input| !vars.contains_key(&input.name)).collect();
+	if let Some(extra) = args.get(missing.len()) {
+		return Err(Error::InputUnexpectedArg(extra.to_string()));
+	}
+	for (input, value) in missing.iter().zip(args) {
+		input.check_value(value)?;
+		vars.insert(input.name.to_string(), value.to_string());
+	}
+
+	// -- The prompted or default values.
+	for input in missing.into_iter().skip(args.len()) {
+		let value = match (interactive, &input.default) {
+			(true, _) => input.prompt_value()?,
+			(false, Some(default)) => default.to_string(),
+			(false, None) => return Err(Error::InputMissing(input.name.to_string())),
+		};
+		vars.insert(input.name.to_string(), value);
+	}
+
+	Ok(vars)
+}
@@ -0,0 +1,106 @@
+use crate::utils::{render_template, Vars};
+use crate::{Error, Result};
+use toml::Value;
+
+const KEY_MATRIX: &str = "matrix";
+
+/// The matrix values of a runner expanded from a `matrix`, e.g., `cargo[target=wasm32-unknown-unknown,profile=release]`.
+#[derive(Debug, Clone)]
+pub struct MatrixEntry {
+	/// The runner name before the expansion (e.g., `cargo`), which still refers to all of its entries.
+	pub base_name: String,
+	/// Position of the entry in the expansion.
+	pub idx: usize,
+}
+
+/// Expand a runner value with a `matrix = { key = [values] }` into one runner value per combination
+/// (the last key varying the fastest), named `name[key=value,...]`, with the `{{key}}` replaced
+/// in its `args`, `env` values, `working_dir`, and `mutex`.
+/// A runner value without a matrix is returned as-is (without entry).
+pub(super) fn expand_matrix(mut value: Value) -> Result<Vec<(Value, Option<MatrixEntry>)>> {
+	let Some(Value::Table(table)) = Some(&mut value) else {
+		return Ok(vec![(value, None)]);
+	};
+	let Some(matrix) = table.remove(KEY_MATRIX) else {
+		return Ok(vec![(value, None)]);
+	};
+	let base_name = table.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+	let invalid = |reason: String| Error::InvalidMatrix(base_name.to_string(), reason);
+
+	// -- Collect the matrix keys and values (as strings).
+	let Value::Table(matrix) = matrix else {
+		return Err(invalid("should be a table of arrays".to_string()));
+	};
+	let mut dims: Vec<(String, Vec<String>)> = Vec::new();
+	for (key, values) in matrix {
+		let Value::Array(values) = values else {
+			return Err(invalid(format!("'{key}' should be an array")));
+		};
+		if values.is_empty() {
+			return Err(invalid(format!("'{key}' has no values")));
+		}
+		let values = values
+			.into_iter()
+			.map(|v| match v {
+				Value::String(text) => text,
+				other => other.to_string(),
+			})
+			.collect();
+		dims.push((key, values));
+	}
+
+	// -- Build the combinations (cartesian product).
+	let mut combos: Vec<Vec<(String, String)>> = vec![Vec::new()];
+	for (key, values) in dims.iter() {
+		combos = combos
+			.into_iter()
+			.flat_map(|combo| {
+				values.iter().map(move |v| {
+					let mut combo = combo.clone();
+					combo.push((key.to_string(), v.to_string()));
+					combo
+				})
+			})
+			.collect();
+	}
+
+	// -- Build the runner value of each combination.
+	let mut runners = Vec::new();
+	for (idx, values) in combos.into_iter().enumerate() {
+		let vars: Vars = values.iter().cloned().collect();
+		let mut table = table.clone();
+
+		let suffix: Vec<String> = values.iter().map(|(k, v)| format!("{k}={v}")).collect();
+		table.insert(
+			"name".to_string(),
+			Value::String(format!("{base_name}[{}]", suffix.join(","))),
+		);
+		for key in ["args", "mutex"] {
+			if let Some(Value::Array(items)) = table.get_mut(key) {
+				items.iter_mut().for_each(|item| render_value(item, &vars));
+			}
+		}
+		for key in ["env", "ready_when", "wait_for"] {
+			if let Some(Value::Table(values)) = table.get_mut(key) {
+				values.iter_mut().for_each(|(_, val)| render_value(val, &vars));
+			}
+		}
+		if let Some(dir) = table.get_mut("working_dir") {
+			render_value(dir, &vars);
+		}
+
+		let entry = MatrixEntry {
+			base_name: base_name.to_string(),
+			idx,
+		};
+		runners.push((Value::Table(table), Some(entry)));
+	}
+
+	Ok(runners)
+}
+
+fn render_value(value: &mut Value, vars: &Vars) {
+	if let Value::String(text) = value {
+		*text = render_template(text, vars);
+	}
+}
@@ -1,18 +1,32 @@
 // region:    --- Modules
 
+mod action;
+mod duration;
+mod hooks;
+mod input;
+mod matrix;
+mod output;
 mod runner;
 
 // -- Flatten
+pub use action::*;
+pub use duration::*;
+pub use hooks::*;
+pub use input::*;
+pub use matrix::MatrixEntry;
+pub use output::*;
 pub use runner::*;
 use toml::{Table, Value}; // Wide for now.
 
 // -- Imports
 use crate::{Error, Result};
+use matrix::expand_matrix;
 use serde::Deserialize;
 use serde_derive::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
 use std::{fs, mem};
+use strsim::damerau_levenshtein;
 
 // endregion: --- Modules
 
@@ -21,6 +35,13 @@ const AWESOME_FILE_NAME: &str = "Awesome.toml";
 
 const KEY_RUNNERS: &str = "runners";
 const KEY_RUNNER: &str = "runner";
+const KEY_OUTPUT: &str = "output";
+const KEY_LOGS: &str = "logs";
+const KEY_HOOKS: &str = "hooks";
+const KEY_DEFAULT: &str = "default";
+
+/// The `awesome` subcommands, which take over a group or solo runner of the same name.
+const RESERVED_RUN_REFS: [&str; 2] = ["logs", "completions"];
 
 // --- Config Types
 #[derive(Debug, Deserialize)]
@@ -29,6 +50,14 @@ pub struct Config {
 	pub grouped_runners: HashMap<String, Vec<Runner>>,
 	/// Runner per runner name `[[runner]] name = _runner_name_`
 	pub solo_runners: HashMap<String, Runner>,
+	/// Output settings of the concurrent runners `[output]`
+	pub output: OutputConfig,
+	/// Runner log files settings `[logs]`
+	pub logs: LogsConfig,
+	/// Hooks by group name `[hooks._group_name_]`
+	pub hooks: HashMap<String, Hooks>,
+	/// The run ref to run when `awesome` is run without one (`default = "dev"`)
+	pub default: Option<String>,
 }
 
 impl Config {
@@ -47,6 +76,135 @@ impl Config {
 	pub fn get_solo_runner<'a>(&'a self, name: &str) -> Option<&'a Runner> {
 		self.solo_runners.get(name)
 	}
+
+	/// The hooks of the group of a run ref (`group_name` or `group_name.runner_name`), if any.
+	pub fn get_hooks_for_ref(&self, run_ref: &str) -> Option<&Hooks> {
+		let group_name = run_ref.split_once('.').map(|(group, _)| group).unwrap_or(run_ref);
+		self.grouped_runners
+			.contains_key(group_name)
+			.then(|| self.hooks.get(group_name))
+			.flatten()
+	}
+
+	/// All of the runners (grouped and solo).
+	pub fn all_runners(&self) -> impl Iterator<Item = &Runner> {
+		self.grouped_runners.values().flatten().chain(self.solo_runners.values())
+	}
+
+	/// Get the runners for a run ref, which can be
+	/// `group_name`, `group_name.runner_name`, or `solo_runner_name`
+	/// (a runner name before its `matrix` expansion refers to all of its entries).
+	pub fn get_runners_for_ref<'a>(&'a self, run_ref: &str) -> Option<Vec<&'a Runner>> {
+		match run_ref.split_once('.') {
+			// If two parts, then, we have a group_name.runner_name
+			Some((group_name, runner_name)) => {
+				self.get_grouped_runner(group_name, runner_name).map(|r| vec![r]).or_else(|| {
+					let group = self.grouped_runners.get(group_name)?;
+					non_empty(group.iter().filter(|r| r.matrix_base_name() == Some(runner_name)).collect())
+				})
+			}
+			// otherwise, we just have a group or a solo runner
+			None => self
+				.get_runners(run_ref)
+				.or_else(|| self.get_solo_runner(run_ref).map(|r| vec![r]))
+				.or_else(|| {
+					let mut entries: Vec<&Runner> = self
+						.solo_runners
+						.values()
+						.filter(|r| r.matrix_base_name() == Some(run_ref))
+						.collect();
+					entries.sort_by_key(|r| r.matrix_entry.as_ref().map(|e| e.idx));
+					non_empty(entries)
+				}),
+		}
+	}
+}
+
+// region:    --- Run Ref Suggestions
+/// Max number of "did you mean" suggestions.
+const MAX_SUGGESTIONS: usize = 3;
+
+impl Config {
+	/// The error for a run ref without runners, with the closest run refs (by edit distance), and the runners
+	/// of its group when the group matched but not the runner.
+	pub fn run_ref_not_found(&self, run_ref: &str) -> Error {
+		let group = run_ref.split_once('.').and_then(|(group_name, runner_name)| {
+			let runners = self.grouped_runners.get(group_name)?;
+			Some((group_name, runner_name, runners))
+		});
+
+		let mut hints = Vec::new();
+		match group {
+			Some((group_name, runner_name, runners)) => {
+				let names = base_names(runners.iter());
+				let candidates = names
+					.iter()
+					.map(|name| (format!("{group_name}.{name}"), vec![name.to_string()]));
+				hints.extend(did_you_mean(&closest(runner_name, candidates)));
+				hints.push(format!("Runners of group '{group_name}': {}.", names.join(", ")));
+			}
+			None => {
+				// Note: The grouped runners also match by their runner name alone (e.g., `server` for `dev.server`).
+				let mut candidates: Vec<(String, Vec<String>)> = Vec::new();
+				for (group_name, runners) in self.grouped_runners.iter() {
+					candidates.push((group_name.to_string(), vec![group_name.to_string()]));
+					for name in base_names(runners.iter()) {
+						let full_ref = format!("{group_name}.{name}");
+						candidates.push((full_ref.to_string(), vec![full_ref, name.to_string()]));
+					}
+				}
+				for name in base_names(self.solo_runners.values()) {
+					candidates.push((name.to_string(), vec![name.to_string()]));
+				}
+				hints.extend(did_you_mean(&closest(run_ref, candidates)));
+			}
+		}
+
+		let hints: String = hints.iter().map(|hint| format!(" {hint}")).collect();
+		Error::RunRefNotFound(run_ref.to_string(), hints)
+	}
+}
+
+/// The run refs of the `candidates` closest to `target`, the closest first (by name when equal), within an
+/// edit distance of a third of the `target` length (at least 1). Each candidate is a run ref, with the keys
+/// it matches by (e.g., `dev.server` by `dev.server` and `server`).
+fn closest(target: &str, candidates: impl IntoIterator<Item = (String, Vec<String>)>) -> Vec<String> {
+	let max_distance = (target.chars().count() / 3).max(1);
+	let mut matches: Vec<(usize, String)> = candidates
+		.into_iter()
+		.filter_map(|(run_ref, keys)| {
+			let distance = keys.iter().map(|key| damerau_levenshtein(target, key)).min()?;
+			(distance <= max_distance).then_some((distance, run_ref))
+		})
+		.collect();
+	matches.sort();
+	matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, run_ref)| run_ref).collect()
+}
+
+fn did_you_mean(run_refs: &[String]) -> Option<String> {
+	let quoted: Vec<String> = run_refs.iter().map(|run_ref| format!("'{run_ref}'")).collect();
+	match quoted.as_slice() {
+		[] => None,
+		[one] => Some(format!("Did you mean {one}?")),
+		many => Some(format!("Did you mean one of {}?", many.join(", "))),
+	}
+}
+
+/// The runner names before their matrix expansion (without duplicates, in order).
+fn base_names<'a>(runners: impl Iterator<Item = &'a Runner>) -> Vec<&'a str> {
+	let mut names: Vec<&str> = Vec::new();
+	for runner in runners {
+		let name = runner.matrix_base_name().unwrap_or(&runner.name);
+		if !names.contains(&name) {
+			names.push(name);
+		}
+	}
+	names
+}
+// endregion: --- Run Ref Suggestions
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+	(!items.is_empty()).then_some(items)
 }
 
 // --- Awesome.toml generator / parser
@@ -106,7 +264,7 @@ impl RunnerHolder {
 /// TODOS:
 /// - Check that no name conflict between the solo runner and group names
 /// - Check that no name conflict within a group
-fn parse_awesome_toml(toml_str: &str) -> Result<Config> {
+pub(crate) fn parse_awesome_toml(toml_str: &str) -> Result<Config> {
 	// let config = toml::from_str::<Value>(toml_str).map_err(Error::FailParsingConfig)?;
 	let mut root_table: Table = toml_str.parse().map_err(Error::FailParsingConfig)?;
 
@@ -191,6 +349,25 @@ fn parse_awesome_toml(toml_str: &str) -> Result<Config> {
 		}
 	}
 
+	// -- Parse the output and logs configs
+	let output = match root_table.remove(KEY_OUTPUT) {
+		Some(value) => OutputConfig::deserialize(value).map_err(Error::FailParsingOutputConfig)?,
+		None => OutputConfig::default(),
+	};
+	let logs = match root_table.remove(KEY_LOGS) {
+		Some(value) => LogsConfig::deserialize(value).map_err(Error::FailParsingLogsConfig)?,
+		None => LogsConfig::default(),
+	};
+	let hooks = match root_table.remove(KEY_HOOKS) {
+		Some(value) => HashMap::<String, Hooks>::deserialize(value).map_err(Error::FailParsingHooksConfig)?,
+		None => HashMap::new(),
+	};
+	let default = match root_table.remove(KEY_DEFAULT) {
+		Some(Value::String(run_ref)) => Some(run_ref),
+		Some(other) => return Err(Error::InvalidDefaultRef(other)),
+		None => None,
+	};
+
 	// -- Build the config properties
 	let mut grouped_runners: HashMap<String, Vec<Runner>> = HashMap::new();
 	let mut solo_runners: HashMap<String, Runner> = HashMap::new();
@@ -200,20 +377,41 @@ fn parse_awesome_toml(toml_str: &str) -> Result<Config> {
 			return Err(Error::StillHaveUnresolvedRefedRunners(runner_holder.value));
 		}
 
-		let runner: Runner = Runner::deserialize(runner_holder.value).map_err(Error::FailParsingRunner)?;
-		match runner_holder.group {
-			Some(group) => {
-				grouped_runners.entry(group).or_default().push(runner);
-			}
-			None => {
-				solo_runners.insert(runner.name.to_string(), runner);
+		// Note: A runner with a `matrix` gets expanded into one runner per combination.
+		for (value, matrix_entry) in expand_matrix(runner_holder.value)? {
+			let mut runner: Runner = Runner::deserialize(value).map_err(Error::FailParsingRunner)?;
+			runner.group.clone_from(&runner_holder.group);
+			runner.matrix_entry = matrix_entry;
+			runner.init_action()?;
+			match &runner_holder.group {
+				Some(group) => {
+					grouped_runners.entry(group.to_string()).or_default().push(runner);
+				}
+				None => {
+					solo_runners.insert(runner.name.to_string(), runner);
+				}
 			}
 		}
 	}
 
+	// -- Check that no run ref is taken over by a subcommand
+	let solo_names = solo_runners.values().map(|r| r.matrix_base_name().unwrap_or(&r.name));
+	if let Some(name) = grouped_runners
+		.keys()
+		.map(String::as_str)
+		.chain(solo_names)
+		.find(|n| RESERVED_RUN_REFS.contains(n))
+	{
+		return Err(Error::ReservedRunRef(name.to_string()));
+	}
+
 	Ok(Config {
 		grouped_runners,
 		solo_runners,
+		output,
+		logs,
+		hooks,
+		default,
 	})
 }
 
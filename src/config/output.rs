@@ -0,0 +1,93 @@
+use serde_derive::Deserialize;
+
+/// The `[output]` table of the `Awesome.toml`, for the output of the concurrent runners.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+	/// Prefix of each line. `{name}` gets replaced by the runner name.
+	pub prefix: String,
+	/// Color the prefix (also disabled when `NO_COLOR` is set or stdout is not a terminal).
+	pub color: bool,
+	/// Print a timestamp before each line.
+	pub timestamps: bool,
+	/// strftime like format of the timestamps.
+	pub timestamp_format: String,
+}
+
+impl Default for OutputConfig {
+	fn default() -> Self {
+		Self {
+			prefix: "[{name}]".to_string(),
+			color: true,
+			timestamps: false,
+			timestamp_format: "%H:%M:%S".to_string(),
+		}
+	}
+}
+
+/// The `[logs]` table of the `Awesome.toml`, for the runner log files.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LogsConfig {
+	/// Write the output of the concurrent (and parallel) runners to `{dir}/<group>/<runner>.log`
+	/// (or `{dir}/<runner>.log` for solo runners).
+	pub enabled: bool,
+	/// Also log the sequential runners. Off by default, as their output then gets piped rather than the terminal.
+	pub sequential: bool,
+	/// Log directory, relative to the `Awesome.toml` directory.
+	pub dir: String,
+	/// Size (in bytes) after which a log file gets rotated.
+	pub max_size: u64,
+	/// Number of rotated files to keep (`<runner>.log.1` being the most recent).
+	pub max_files: usize,
+}
+
+impl Default for LogsConfig {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			sequential: false,
+			dir: ".awesome/logs".to_string(),
+			max_size: 5 * 1024 * 1024,
+			max_files: 3,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Color {
+	Red,
+	Green,
+	Yellow,
+	Blue,
+	Magenta,
+	Cyan,
+	White,
+	Gray,
+}
+
+impl Color {
+	/// The colors given in turn to the concurrent runners without a `color` property.
+	pub const PALETTE: [Color; 6] = [
+		Color::Cyan,
+		Color::Yellow,
+		Color::Green,
+		Color::Magenta,
+		Color::Blue,
+		Color::Red,
+	];
+
+	pub fn ansi_code(&self) -> &'static str {
+		match self {
+			Color::Red => "31",
+			Color::Green => "32",
+			Color::Yellow => "33",
+			Color::Blue => "34",
+			Color::Magenta => "35",
+			Color::Cyan => "36",
+			Color::White => "37",
+			Color::Gray => "90",
+		}
+	}
+}
@@ -1,22 +1,93 @@
-use crate::exec::{spawn_and_wait, spawn_tokio};
-use crate::utils::W;
-use crate::Result;
+use crate::actions::run_action;
+use crate::config::{
+	deserialize_duration, deserialize_opt_duration, Action, ActionKind, ActionParams, Color, Input, MatrixEntry,
+};
+use crate::exec::{spawn_and_wait, spawn_output, spawn_tokio, InFlight, SpawnOptions, StdinSource, StdioOptions, Stop};
+use crate::output::{pipe_child_output, LineMatcher, OutputPipe, StdoutBuffer};
+use crate::probe::{file_exists, http_status, parse_http_url, tcp_accepts};
+use crate::utils::{has_template_var, render_template, Vars, W};
+use crate::{Error, Result};
+use regex::Regex;
 use serde_derive::Deserialize;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
-use std::time::Duration;
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
 use tokio::process::Child;
+use tokio::task::{block_in_place, JoinHandle};
 use tokio::time::sleep;
 
+const READY_POLL_DELAY: u64 = 200; // in ms
+const DEFAULT_READY_TIMEOUT: u64 = 30_000; // in ms
+const DEFAULT_WAIT_TIMEOUT: u64 = 30_000; // in ms
+const DEFAULT_WAIT_INTERVAL: u64 = 200; // in ms
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+const DEFAULT_RESTART_BACKOFF: u64 = 1000; // in ms
+const MAX_RESTART_DELAY: u64 = 60_000; // in ms
+const DEFAULT_STOP_TIMEOUT: u64 = 5000; // in ms
+const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+const DEFAULT_RETRY_DELAY: u64 = 1000; // in ms
+const DEFAULT_RETRY_BACKOFF: f64 = 2.0;
+/// The template var of the `for_each` items.
+const ITEM_VAR: &str = "item";
+
 #[derive(Debug, Deserialize)]
 pub struct Runner {
 	pub name: String,
 
+	/// Shown in the runner picker (when `awesome` is run without a run ref).
+	pub description: Option<String>,
+
+	/// The group name (from `[[runners._group_name_]]`), None for solo runners.
+	#[serde(skip)]
+	pub group: Option<String>,
+
+	/// The matrix values, for a runner expanded from a `matrix` (at parse time).
+	#[serde(skip)]
+	pub matrix_entry: Option<MatrixEntry>,
+
 	pub working_dir: Option<String>,
+	/// The program to execute (not needed for the built-in `action`).
+	#[serde(default)]
 	pub cmd: String,
 	pub args: Option<Vec<String>>,
 
+	/// The built-in action (`action = "rm"`, ...) to run instead of a `cmd`, with its parameters
+	/// (e.g., `path`, `from`, `to`) in the runner table.
+	#[serde(rename = "action")]
+	action_kind: Option<ActionKind>,
+	#[serde(flatten)]
+	action_params: ActionParams,
+	/// The action built from `action_kind` and `action_params` (see `init_action`).
+	#[serde(skip)]
+	pub action: Option<Action>,
+
+	/// Instead of a `cmd`, wait until the probes pass (e.g., a service started by a concurrent runner).
+	pub wait_for: Option<WaitFor>,
+
+	/// Environment variables added to the process environment (over the session vars).
+	pub env: Option<HashMap<String, String>>,
+
+	/// For the matrix entries and the for_each items (sequential), max number of entries/batches running
+	/// at the same time (default 1, one after the other).
+	#[serde(default = "default_parallel")]
+	pub parallel: usize,
+
 	pub when: Option<When>,
 
+	/// The input parameters (from the command line, or prompted), available as `{{name}}` to the session runners.
+	#[serde(default)]
+	pub inputs: Vec<Input>,
+
+	/// A question (e.g., "Reset local database?") to confirm (y/N) before running the runner.
+	pub confirm: Option<String>,
+
+	/// What to do when the `confirm` is not answered (empty answer, or stdin not a terminal, e.g., in CI),
+	/// "skip" (default), "abort", or "yes". A "no" answer skips the runner, or aborts the session with "abort".
+	#[serde(default)]
+	pub confirm_default: ConfirmDefault,
+
 	#[serde(default)]
 	pub wait_before: u64,
 
@@ -25,6 +96,78 @@ pub struct Runner {
 
 	#[serde(default)]
 	pub end_all_on_exit: bool,
+
+	/// When the runner fails, keep running the next runners, and do not fail the session.
+	#[serde(default)]
+	pub continue_on_error: bool,
+
+	/// Max run time of the process (e.g., "5m" or in ms), after which its process tree gets terminated.
+	#[serde(default, deserialize_with = "deserialize_opt_duration")]
+	pub timeout: Option<Duration>,
+
+	/// For sequential runners, retry the command when it fails.
+	pub retry: Option<Retry>,
+
+	/// Exit codes considered as a success (default `[0]`), e.g., `[0, 1]` for tools exiting 1 for "no changes".
+	pub success_codes: Option<Vec<i32>>,
+
+	/// Regex matched against each stdout/stderr line. The runner fails if any line matches.
+	pub fail_on_output: Option<String>,
+
+	/// Regex matched against each stdout/stderr line. The runner fails if no line matches.
+	pub expect_output: Option<String>,
+
+	/// Run the command to completion, and capture its stdout into a session var, available to the next runners
+	/// as an env var and as `{{NAME}}` in their cmd, args, and working_dir.
+	pub capture: Option<Capture>,
+
+	/// Names of the shared resources (e.g., "db", "cargo-target") this (sequential) runner must not use at the
+	/// same time as other runners (e.g., parallel matrix entries or for_each items), with `{{NAME}}` vars.
+	#[serde(default)]
+	pub mutex: Vec<String>,
+
+	/// Run the (sequential) runner once per item (or batch of items), with the `{{item}}` replaced.
+	pub for_each: Option<ForEach>,
+
+	/// The process stdin, "inherit" (default for sequential runners), "null" (default for concurrent runners),
+	/// `{ file = "..." }`, `{ text = "..." }`, or `{ from = "group.runner" }` (the stdout of a runner of this session).
+	pub stdin: Option<Stdin>,
+
+	/// Redirect the process stdout to this file (relative to the Awesome.toml dir, created or truncated).
+	pub stdout: Option<String>,
+
+	/// Redirect the process stderr to this file (relative to the Awesome.toml dir, created or truncated).
+	pub stderr: Option<String>,
+
+	/// For concurrent runners (without `end_all_on_exit`), when to restart the process when it exits.
+	#[serde(default)]
+	pub restart: RestartPolicy,
+
+	/// For concurrent runners, what to do when the process exits (takes precedence over
+	/// `end_all_on_exit` and `restart`).
+	pub on_exit: Option<OnExit>,
+
+	#[serde(default = "default_max_restarts")]
+	pub max_restarts: u32,
+
+	/// Delay before the first restart (e.g., "1s", or in ms), doubled for each following restart.
+	#[serde(default = "default_restart_backoff", deserialize_with = "deserialize_duration")]
+	pub restart_backoff: Duration,
+
+	/// For concurrent runners, the signal sent to the process tree to stop it (default "SIGTERM").
+	#[serde(default)]
+	pub stop_signal: StopSignal,
+
+	/// Time given to the process tree to exit after the stop_signal (e.g., "5s", or in ms),
+	/// before being killed (SIGKILL).
+	#[serde(default = "default_stop_timeout", deserialize_with = "deserialize_duration")]
+	pub stop_timeout: Duration,
+
+	/// For concurrent runners, the probe(s) to pass before the next runners get executed.
+	pub ready_when: Option<ReadyWhen>,
+
+	/// For concurrent runners, the color of the output prefix (default from the `Color::PALETTE`).
+	pub color: Option<Color>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +175,400 @@ pub struct When {
 	no_file_at: Option<String>,
 }
 
+/// Readiness probes of a concurrent runner. When more than one probe is set, all must pass.
+#[derive(Debug, Deserialize)]
+pub struct ReadyWhen {
+	/// `host:port` (or just `port`) that should accept TCP connections.
+	pub tcp: Option<String>,
+	/// Regex matched against each stdout/stderr line of the runner.
+	pub output: Option<String>,
+	/// File path (relative to the root dir) that should exist.
+	pub file: Option<String>,
+	/// Max time to wait for the runner to be ready (e.g., "30s", or in ms).
+	#[serde(default = "default_ready_timeout", deserialize_with = "deserialize_duration")]
+	pub timeout: Duration,
+}
+
+/// The probes of a `wait_for` runner, e.g., `{ tcp = "localhost:5432" }`, `{ file = "target/.ready" }`,
+/// or `{ http = "http://127.0.0.1:8080/health", status = 200 }`. When more than one probe is set, all must pass.
+#[derive(Debug, Deserialize)]
+pub struct WaitFor {
+	/// `host:port` (or just `port`) that should accept TCP connections.
+	pub tcp: Option<String>,
+	/// File path (relative to the root dir) that should exist.
+	pub file: Option<String>,
+	/// Plain `http://` url (e.g., a local health endpoint) that should respond with the `status`.
+	pub http: Option<String>,
+	/// The expected `http` status (default any 2xx).
+	pub status: Option<u16>,
+	/// Max time to wait for the probes to pass (e.g., "1m", or in ms).
+	#[serde(default = "default_wait_timeout", deserialize_with = "deserialize_duration")]
+	pub timeout: Duration,
+	/// Delay between two polls of the probes (e.g., "500ms", or in ms).
+	#[serde(default = "default_wait_interval", deserialize_with = "deserialize_duration")]
+	pub interval: Duration,
+}
+
+fn default_wait_timeout() -> Duration {
+	Duration::from_millis(DEFAULT_WAIT_TIMEOUT)
+}
+
+fn default_wait_interval() -> Duration {
+	Duration::from_millis(DEFAULT_WAIT_INTERVAL)
+}
+
+impl WaitFor {
+	/// Check that at least one probe is set, and that the `http` url is supported. Returns the reason otherwise.
+	fn check(&self) -> core::result::Result<(), String> {
+		if self.tcp.is_none() && self.file.is_none() && self.http.is_none() {
+			return Err("requires 'tcp', 'file', or 'http'".to_string());
+		}
+		if let Some(url) = self.http.as_deref().filter(|url| parse_http_url(url).is_none()) {
+			return Err(format!("'{url}' is not a valid 'http://' url"));
+		}
+		if self.status.is_some() && self.http.is_none() {
+			return Err("'status' requires 'http'".to_string());
+		}
+		Ok(())
+	}
+
+	/// Poll the probes (with the `vars`) until all pass, or the `timeout` (or the session `deadline`) passes.
+	/// The `file` is relative to `root_dir` (like the `ready_when.file`).
+	async fn wait(&self, name: &str, root_dir: &Path, vars: &Vars, deadline: Option<Instant>) -> Result<()> {
+		let tcp = self.tcp.as_ref().map(|addr| render_template(addr, vars));
+		let file = self.file.as_ref().map(|file| render_template(file, vars));
+		let http = self.http.as_ref().map(|url| render_template(url, vars));
+
+		let probes: Vec<String> = [("tcp", &tcp), ("file", &file), ("http", &http)]
+			.into_iter()
+			.filter_map(|(kind, target)| target.as_ref().map(|t| format!("{kind} {t}")))
+			.collect();
+		let probes = probes.join(", ");
+		println!("> wait_for: {probes}");
+
+		let file_path = file.as_ref().map(|file| root_dir.join(file));
+		let start = Instant::now();
+		let deadline_at = match deadline {
+			Some(deadline) => deadline.min(start + self.timeout),
+			None => start + self.timeout,
+		};
+
+		loop {
+			let file_ready = file_path.as_deref().is_none_or(file_exists);
+			let tcp_ready = match tcp.as_deref() {
+				Some(addr) if file_ready => tcp_accepts(addr).await,
+				Some(_) => false,
+				None => true,
+			};
+			let http_ready = match http.as_deref() {
+				Some(url) if file_ready && tcp_ready => {
+					http_status(url).await.is_some_and(|s| self.is_expected_status(s))
+				}
+				Some(_) => false,
+				None => true,
+			};
+
+			if file_ready && tcp_ready && http_ready {
+				println!("Runner '{name}' is ready");
+				return Ok(());
+			}
+
+			let now = Instant::now();
+			if now >= deadline_at {
+				return Err(Error::WaitForTimeout(name.to_string(), probes, start.elapsed()));
+			}
+
+			sleep(self.interval.min(deadline_at - now)).await;
+		}
+	}
+
+	fn is_expected_status(&self, status: u16) -> bool {
+		match self.status {
+			Some(expected) => status == expected,
+			None => (200..300).contains(&status),
+		}
+	}
+}
+
+fn default_parallel() -> usize {
+	1
+}
+
+fn default_ready_timeout() -> Duration {
+	Duration::from_millis(DEFAULT_READY_TIMEOUT)
+}
+
+/// `capture = "GIT_SHA"`, or `capture = { var = "VERSION", json = "/version", regex = '^v(.*)' }`.
+/// The output gets trimmed, and then, extracted with the optional `json` pointer, and then, the `regex`
+/// (first capture group, or the whole match).
+#[derive(Debug, Deserialize)]
+#[serde(from = "CaptureValue")]
+pub struct Capture {
+	pub var: String,
+	pub json: Option<String>,
+	pub regex: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CaptureValue {
+	Var(String),
+	Table {
+		var: String,
+		json: Option<String>,
+		regex: Option<String>,
+	},
+}
+
+impl From<CaptureValue> for Capture {
+	fn from(val: CaptureValue) -> Self {
+		match val {
+			CaptureValue::Var(var) => Capture {
+				var,
+				json: None,
+				regex: None,
+			},
+			CaptureValue::Table { var, json, regex } => Capture { var, json, regex },
+		}
+	}
+}
+
+impl Capture {
+	/// Extract the value from the command output.
+	pub fn extract(&self, output: &str) -> Result<String> {
+		let mut value = output.trim().to_string();
+
+		if let Some(pointer) = &self.json {
+			let json: serde_json::Value = serde_json::from_str(&value)
+				.map_err(|ex| Error::CaptureInvalidJson(self.var.to_string(), ex.to_string()))?;
+			let found = json
+				.pointer(pointer)
+				.ok_or_else(|| Error::CaptureNotFound(self.var.to_string(), format!("json pointer '{pointer}'")))?;
+			value = match found {
+				serde_json::Value::String(text) => text.to_string(),
+				other => other.to_string(),
+			};
+		}
+
+		if let Some(pattern) = &self.regex {
+			let re = Regex::new(pattern).map_err(|ex| Error::InvalidRegex(pattern.to_string(), ex.to_string()))?;
+			let caps = re
+				.captures(&value)
+				.ok_or_else(|| Error::CaptureNotFound(self.var.to_string(), format!("regex '{pattern}'")))?;
+			value = caps
+				.get(1)
+				.or_else(|| caps.get(0))
+				.map(|m| m.as_str())
+				.unwrap_or_default()
+				.to_string();
+		}
+
+		Ok(value.trim().to_string())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stdin {
+	Inherit,
+	Null,
+	/// File path (relative to the Awesome.toml dir).
+	File(String),
+	Text(String),
+	/// The stdout (so far) of another runner of the session, e.g., `{ from = "group_name.runner_name" }`.
+	From(String),
+}
+
+/// `for_each = { glob = "migrations/*.sql" }` (the matching paths, relative to the Awesome.toml dir),
+/// or `for_each = { cmd = "git ls-files '*.proto'" }` (the non-empty lines of the shell command output).
+#[derive(Debug, Deserialize)]
+pub struct ForEach {
+	pub glob: Option<String>,
+	pub cmd: Option<String>,
+	/// Number of items per invocation (default 1). When more than one, an arg with `{{item}}` gets repeated
+	/// for each item of the batch (xargs-style), and `{{item}}` elsewhere is the space separated items.
+	#[serde(default = "default_batch")]
+	pub batch: usize,
+}
+
+fn default_batch() -> usize {
+	1
+}
+
+#[cfg(target_os = "windows")]
+const SHELL: [&str; 2] = ["cmd", "/C"];
+#[cfg(not(target_os = "windows"))]
+const SHELL: [&str; 2] = ["sh", "-c"];
+
+impl ForEach {
+	/// The items of the runner (`name` for the errors), with the session vars in the `cmd`.
+	pub fn items(&self, name: &str, vars: &Vars) -> Result<Vec<String>> {
+		let invalid = |reason: String| Error::ForEachInvalid(name.to_string(), reason);
+		match (&self.glob, &self.cmd) {
+			(Some(pattern), None) => {
+				let paths = glob::glob(pattern).map_err(|ex| invalid(format!("glob '{pattern}'. Cause: {ex}")))?;
+				paths
+					.map(|path| path.map(|p| p.to_string_lossy().to_string()).map_err(|ex| Error::IO(ex.into())))
+					.collect()
+			}
+			(None, Some(cmd)) => {
+				let cmd = render_template(cmd, vars);
+				let spawn_options = SpawnOptions {
+					env: vars.clone(),
+					..Default::default()
+				};
+				let output = spawn_output(None, SHELL[0], &[SHELL[1], &cmd], true, &spawn_options)?;
+				Ok(output
+					.lines()
+					.map(str::trim)
+					.filter(|line| !line.is_empty())
+					.map(String::from)
+					.collect())
+			}
+			_ => Err(invalid("should have either 'glob' or 'cmd'".to_string())),
+		}
+	}
+}
+
+/// The retries of a (sequential) runner command, e.g., `{ attempts = 3, delay = "2s", backoff = 2.0, on_codes = [1] }`.
+#[derive(Debug, Deserialize)]
+pub struct Retry {
+	/// Max number of attempts (including the first one).
+	#[serde(default = "default_retry_attempts")]
+	pub attempts: u32,
+	/// Delay before the first retry (e.g., "2s", or in ms).
+	#[serde(default = "default_retry_delay", deserialize_with = "deserialize_duration")]
+	pub delay: Duration,
+	/// Multiplier of the delay for each following retry.
+	#[serde(default = "default_retry_backoff")]
+	pub backoff: f64,
+	/// Only retry on these exit codes (when empty, retry on any failure, including timeouts).
+	#[serde(default)]
+	pub on_codes: Vec<i32>,
+}
+
+impl Retry {
+	/// The delay before the next attempt, or None when the failed `attempt` (starting at 1) should not be retried.
+	pub fn next_delay(&self, attempt: u32, err: &Error) -> Option<Duration> {
+		if attempt >= self.attempts {
+			return None;
+		}
+		let retryable = match err {
+			Error::ExecStatus(_, status) => {
+				self.on_codes.is_empty() || status.code().is_some_and(|code| self.on_codes.contains(&code))
+			}
+			Error::ExecTimeout(..) | Error::OutputFailMatched(..) | Error::OutputNotExpected(..) => {
+				self.on_codes.is_empty()
+			}
+			_ => false,
+		};
+		if !retryable {
+			return None;
+		}
+		let factor = self.backoff.max(0.0).powi(attempt as i32 - 1);
+		Some(Duration::try_from_secs_f64(self.delay.as_secs_f64() * factor).unwrap_or(self.delay))
+	}
+}
+
+fn default_retry_attempts() -> u32 {
+	DEFAULT_RETRY_ATTEMPTS
+}
+
+fn default_retry_delay() -> Duration {
+	Duration::from_millis(DEFAULT_RETRY_DELAY)
+}
+
+fn default_retry_backoff() -> f64 {
+	DEFAULT_RETRY_BACKOFF
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmDefault {
+	/// Skip the runner (the next runners still run).
+	#[default]
+	Skip,
+	/// Fail the runner, which stops the session (unless `continue_on_error`).
+	Abort,
+	/// Run the runner.
+	Yes,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+	#[default]
+	No,
+	OnFailure,
+	Always,
+}
+
+/// The `on_exit` actions of a concurrent runner, by exit status.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OnExit {
+	pub success: ExitAction,
+	pub failure: ExitAction,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitAction {
+	/// Terminate all the other runners and end the session.
+	EndAll,
+	/// Restart the runner (bounded by `max_restarts`, delayed by `restart_backoff`).
+	Restart,
+	#[default]
+	Ignore,
+	/// Same as `end_all`, but the session fails even if the runner exited successfully.
+	FailSession,
+	/// Run another runner, e.g., `{ run = "group_name.runner_name" }`.
+	Run(String),
+}
+
+/// The signal to stop a runner process tree. Accepts "SIGTERM", "TERM", "term", ...
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum StopSignal {
+	Int,
+	#[default]
+	Term,
+	Hup,
+	Quit,
+	Kill,
+}
+
+impl TryFrom<String> for StopSignal {
+	type Error = String;
+
+	fn try_from(val: String) -> core::result::Result<Self, Self::Error> {
+		let name = val.to_uppercase();
+		let name = name.strip_prefix("SIG").unwrap_or(&name);
+		match name {
+			"INT" => Ok(StopSignal::Int),
+			"TERM" => Ok(StopSignal::Term),
+			"HUP" => Ok(StopSignal::Hup),
+			"QUIT" => Ok(StopSignal::Quit),
+			"KILL" => Ok(StopSignal::Kill),
+			_ => Err(format!(
+				"stop_signal '{val}' not supported (SIGINT, SIGTERM, SIGHUP, SIGQUIT, SIGKILL)"
+			)),
+		}
+	}
+}
+
+fn default_stop_timeout() -> Duration {
+	Duration::from_millis(DEFAULT_STOP_TIMEOUT)
+}
+
+fn default_max_restarts() -> u32 {
+	DEFAULT_MAX_RESTARTS
+}
+
+fn default_restart_backoff() -> Duration {
+	Duration::from_millis(DEFAULT_RESTART_BACKOFF)
+}
+
 // region:    --- Runner Impl
 #[cfg(target_os = "windows")]
 const NPM_CMD: &str = "npm.cmd";
@@ -39,7 +576,58 @@ const NPM_CMD: &str = "npm.cmd";
 const NPM_CMD: &str = "npm";
 
 impl Runner {
-	pub async fn exec(&self) -> Result<Option<Child>> {
+	/// The run ref of this runner, `group_name.runner_name` or `solo_runner_name`.
+	pub fn run_ref(&self) -> String {
+		match &self.group {
+			Some(group) => format!("{group}.{}", self.name),
+			None => self.name.to_string(),
+		}
+	}
+
+	/// Build the `action` from its kind and parameters, check the `wait_for` and the `inputs`, and check that
+	/// the runner has a `cmd`, an `action`, or a `wait_for`.
+	pub fn init_action(&mut self) -> Result<()> {
+		for input in self.inputs.iter() {
+			input.check(&self.name)?;
+		}
+		if let Some(wait_for) = &self.wait_for {
+			wait_for
+				.check()
+				.map_err(|reason| Error::InvalidWaitFor(self.name.to_string(), reason))?;
+		}
+
+		self.action = match self.action_kind {
+			Some(kind) => {
+				let action = Action::new(kind, &self.action_params)
+					.map_err(|reason| Error::InvalidAction(self.name.to_string(), reason))?;
+				Some(action)
+			}
+			None if self.cmd.is_empty() && self.wait_for.is_none() => {
+				return Err(Error::RunnerHasNoCmdOrAction(self.name.to_string()))
+			}
+			None => None,
+		};
+		Ok(())
+	}
+
+	/// The `mutex` names, with the session vars and the for_each items (sorted, without duplicates).
+	pub fn mutex_names(&self, vars: &Vars, items: &[String]) -> BTreeSet<String> {
+		let mut vars = vars.clone();
+		if !items.is_empty() {
+			vars.insert(ITEM_VAR.to_string(), items.join(" "));
+		}
+		self.mutex.iter().map(|name| render_template(name, &vars)).collect()
+	}
+
+	/// The runner name before its `matrix` expansion, if expanded from a matrix.
+	pub fn matrix_base_name(&self) -> Option<&str> {
+		self.matrix_entry.as_ref().map(|entry| entry.base_name.as_str())
+	}
+
+	/// Execute the runner. Sequential runners are awaited (terminated when passing their `timeout` or the
+	/// session deadline), the `capture` runners return their captured value, and concurrent runners
+	/// return their spawned child with the output processed by `output_pipe`.
+	pub async fn exec(&self, mut output_pipe: OutputPipe, ctx: &ExecContext<'_>) -> Result<ExecOutput> {
 		// --- Process the wait_before.
 		if self.wait_before > 0 {
 			println!(
@@ -49,32 +637,268 @@ impl Runner {
 			sleep(Duration::from_millis(self.wait_before)).await;
 		}
 
+		// --- Add the for_each items (as `{{item}}`) to the session vars.
+		let item_vars;
+		let vars = match ctx.items {
+			[] => ctx.vars,
+			items => {
+				let mut vars = ctx.vars.clone();
+				vars.insert(ITEM_VAR.to_string(), items.join(" "));
+				item_vars = vars;
+				&item_vars
+			}
+		};
+
+		// --- Run the built-in action (to completion), or wait for the probes.
+		if self.action.is_some() || self.wait_for.is_some() {
+			if let Some(action) = &self.action {
+				let base_dir = match &self.working_dir {
+					Some(dir) => ctx.root_dir.join(render_template(dir, vars)),
+					None => ctx.root_dir.to_path_buf(),
+				};
+				block_in_place(|| run_action(&action.render(vars), &base_dir, ctx.root_dir))?;
+			} else if let Some(wait_for) = &self.wait_for {
+				wait_for.wait(&self.name, ctx.root_dir, vars, ctx.deadline).await?;
+			}
+			return Ok(ExecOutput::Done);
+		}
+
 		// --- Compute the cmd name.
 		// Note: Special handling of "npm" which on Windows must be called as "npm.cmd"
 		// TODO: Needs to generalize this. Could be more downstream, on ProgramNotFound error.
-		let cmd_str: &str = self.cmd.as_ref();
+		let cmd = render_template(&self.cmd, vars);
+		let cmd_str: &str = cmd.as_ref();
 		let cmd_str = if cmd_str.starts_with("npm") && cmd_str != NPM_CMD {
 			NPM_CMD
 		} else {
 			cmd_str
 		};
 
-		// --- Compute the cmd args and working dir
-		let args: Vec<&str> = W(&self.args).into();
-		let cwd = self.working_dir.as_ref().map(Path::new);
+		// --- Compute the cmd args and working dir (with the session vars)
+		let args: Vec<String> = self
+			.args
+			.iter()
+			.flatten()
+			.flat_map(|arg| render_arg(arg, vars, ctx.items))
+			.collect();
+		let args: Vec<&str> = args.iter().map(String::as_str).collect();
+		let working_dir = self.working_dir.as_ref().map(|dir| render_template(dir, vars));
+		let cwd = working_dir.as_ref().map(Path::new);
+		let mut stdio = self.stdio_options(vars, ctx)?;
+		let mut env = vars.clone();
+		env.extend(
+			self.env
+				.iter()
+				.flatten()
+				.map(|(name, val)| (name.to_string(), render_template(val, vars))),
+		);
+
+		// --- Capture the stdout (buffered, not printed), run to completion like the sequential runners.
+		let capture_buffer = self.capture.as_ref().map(|_| {
+			stdio.stdout = None;
+			output_pipe.quiet_stdout = true;
+			output_pipe.stdout_buffer.get_or_insert_with(StdoutBuffer::default).clone()
+		});
+
+		// --- Add the fail_on_output / expect_output matchers to the output.
+		let checks = self.output_checks()?;
+		output_pipe
+			.matchers
+			.extend(checks.fail_on.iter().chain(checks.expect.iter()).cloned());
 
 		// --- Execute the command
-		if !self.concurrent {
-			spawn_and_wait(cwd, cmd_str, args.as_slice(), true)?;
-			Ok(None)
+		if !self.concurrent || self.capture.is_some() {
+			// Note: Sequential runners keep the terminal stdio, unless their output needs to be logged, checked,
+			//       or buffered.
+			let output_pipe = output_pipe.needs_lines().then_some(output_pipe);
+			let deadline_at = self.timeout.map(|t| Instant::now() + t);
+			let deadline_at = match (deadline_at, ctx.deadline) {
+				(Some(a), Some(b)) => Some(a.min(b)),
+				(a, b) => a.or(b),
+			};
+			let stop = Stop {
+				name: self.name.to_string(),
+				at: deadline_at,
+				stop_signal: self.stop_signal,
+				stop_timeout: self.stop_timeout,
+				in_flight: ctx.in_flight.clone(),
+			};
+			let spawn_options = SpawnOptions {
+				env,
+				stdio,
+				..Default::default()
+			};
+			let status = spawn_and_wait(cwd, cmd_str, args.as_slice(), true, output_pipe, stop, spawn_options).await?;
+			// Note: With the rendered cmd and args in the error (e.g., `{{item}}`).
+			self.check_exit(&status, &checks).map_err(|err| match err {
+				Error::ExecStatus(_, status) => (cmd_str, args.as_slice(), status).into(),
+				err => err,
+			})?;
+			match (&self.capture, capture_buffer) {
+				(Some(capture), Some(buffer)) => {
+					let output = buffer.lock().map(|output| output.to_string()).unwrap_or_default();
+					Ok(ExecOutput::Captured(capture.extract(&output)?))
+				}
+				_ => Ok(ExecOutput::Done),
+			}
 		}
 		// start the concurrent mode and add it in the concurrent watch list.
 		else {
-			let child = spawn_tokio(cwd, cmd_str, args.as_slice(), true)?;
-			Ok(Some(child))
+			let spawn_options = SpawnOptions {
+				pipe_output: true,
+				own_process_group: true,
+				env,
+				stdio,
+			};
+			let mut child = spawn_tokio(cwd, cmd_str, args.as_slice(), true, spawn_options)?;
+
+			// --- Pipe the output, with the ready_when.output matching (if needed).
+			let ready_output = match self.ready_when.as_ref().and_then(|r| r.output.as_deref()) {
+				Some(pattern) => Some(LineMatcher::new(pattern)?),
+				None => None,
+			};
+			output_pipe.matchers.extend(ready_output.iter().cloned());
+			let pumps = pipe_child_output(&mut child, output_pipe);
+
+			Ok(ExecOutput::Spawned(Box::new(RunnerChild {
+				child,
+				pumps,
+				ready_output,
+				checks,
+			})))
+		}
+	}
+
+	/// The stdin (default null for concurrent and parallel runners) and stdout/stderr redirects,
+	/// with the `vars`.
+	fn stdio_options(&self, vars: &Vars, ctx: &ExecContext) -> Result<StdioOptions> {
+		let stdin = match &self.stdin {
+			None if self.concurrent || self.parallel > 1 => StdinSource::Null,
+			None | Some(Stdin::Inherit) => StdinSource::Inherit,
+			Some(Stdin::Null) => StdinSource::Null,
+			Some(Stdin::File(path)) => StdinSource::File(render_template(path, vars).into()),
+			Some(Stdin::Text(text)) => StdinSource::Text(render_template(text, vars)),
+			Some(Stdin::From(run_ref)) => {
+				let text = ctx
+					.outputs
+					.borrow()
+					.get(run_ref)
+					.and_then(|buffer| buffer.lock().ok().map(|text| text.to_string()))
+					.ok_or_else(|| Error::StdinFromNotRun(self.run_ref(), run_ref.to_string()))?;
+				StdinSource::Text(text)
+			}
+		};
+		Ok(StdioOptions {
+			stdin,
+			stdout: self.stdout.as_ref().map(|path| render_template(path, vars).into()),
+			stderr: self.stderr.as_ref().map(|path| render_template(path, vars).into()),
+		})
+	}
+
+	/// Wait until the `ready_when` probes (with the `vars`) of this (concurrent) runner pass.
+	/// Returns immediately if the runner does not have a `ready_when`.
+	pub async fn wait_ready(&self, root_dir: &Path, vars: &Vars, runner_child: &mut RunnerChild) -> Result<()> {
+		let Some(ready_when) = self.ready_when.as_ref() else {
+			return Ok(());
+		};
+
+		println!("Waiting for runner '{}' to be ready", self.name);
+
+		let tcp = ready_when.tcp.as_ref().map(|addr| render_template(addr, vars));
+		let ready_file = ready_when.file.as_ref().map(|file| root_dir.join(render_template(file, vars)));
+
+		// --- Poll the probes until all pass, the child exits, or timeout.
+		let start = Instant::now();
+		loop {
+			let output_ready = runner_child.ready_output.as_ref().is_none_or(LineMatcher::is_matched);
+			let file_ready = ready_file.as_deref().is_none_or(file_exists);
+			let tcp_ready = match tcp.as_deref() {
+				Some(addr) if output_ready && file_ready => tcp_accepts(addr).await,
+				Some(_) => false,
+				None => true,
+			};
+
+			if output_ready && file_ready && tcp_ready {
+				println!("Runner '{}' is ready", self.name);
+				return Ok(());
+			}
+
+			if let Some(status) = runner_child.child.try_wait()? {
+				return Err(Error::RunnerExitedBeforeReady(
+					self.name.to_string(),
+					status.to_string(),
+				));
+			}
+
+			if start.elapsed() >= ready_when.timeout {
+				return Err(Error::RunnerReadyTimeout(self.name.to_string(), ready_when.timeout));
+			}
+
+			sleep(Duration::from_millis(READY_POLL_DELAY)).await;
+		}
+	}
+
+	pub fn output_checks(&self) -> Result<OutputChecks> {
+		let fail_on = self.fail_on_output.as_deref().map(LineMatcher::new).transpose()?;
+		let expect = self.expect_output.as_deref().map(LineMatcher::new).transpose()?;
+		Ok(OutputChecks { fail_on, expect })
+	}
+
+	/// True if the exit code is one of the `success_codes` (or 0 when not defined).
+	pub fn is_success(&self, status: &ExitStatus) -> bool {
+		match &self.success_codes {
+			Some(success_codes) => status.code().is_some_and(|code| success_codes.contains(&code)),
+			None => status.success(),
+		}
+	}
+
+	/// Check the exit status of a process run (with `success_codes`), and then, its output checks.
+	pub fn check_exit(&self, status: &ExitStatus, checks: &OutputChecks) -> Result<()> {
+		if !self.is_success(status) {
+			let args: Vec<&str> = W(&self.args).into();
+			return Err((self.cmd.as_str(), args.as_slice(), *status).into());
+		}
+
+		if let Some(fail_on) = &checks.fail_on {
+			if let Some(line) = fail_on.first_match() {
+				return Err(Error::OutputFailMatched(fail_on.re.to_string(), line.to_string()));
+			}
+		}
+
+		if let Some(expect) = checks.expect.as_ref().filter(|m| !m.is_matched()) {
+			return Err(Error::OutputNotExpected(expect.re.to_string()));
+		}
+
+		Ok(())
+	}
+
+	/// The action to take when the (concurrent) process exited, successfully or not (see `check_exit`).
+	/// From `on_exit` if defined, otherwise, from `end_all_on_exit` and `restart`.
+	pub fn exit_action(&self, success: bool) -> ExitAction {
+		if let Some(on_exit) = &self.on_exit {
+			return if success {
+				on_exit.success.clone()
+			} else {
+				on_exit.failure.clone()
+			};
+		}
+
+		if self.end_all_on_exit {
+			return ExitAction::EndAll;
+		}
+
+		match (self.restart, success) {
+			(RestartPolicy::Always, _) | (RestartPolicy::OnFailure, false) => ExitAction::Restart,
+			_ => ExitAction::Ignore,
 		}
 	}
 
+	/// The delay before the next restart (exponential backoff from `restart_backoff`).
+	pub fn restart_delay(&self, restarts: u32) -> Duration {
+		let delay = self.restart_backoff.saturating_mul(2u32.saturating_pow(restarts));
+		delay.min(Duration::from_millis(MAX_RESTART_DELAY))
+	}
+
 	pub fn should_run(&self, root_dir: &Path) -> Result<ShouldRun> {
 		let no_file_at = self.when.as_ref().and_then(|w| w.no_file_at.as_ref());
 
@@ -89,6 +913,65 @@ impl Runner {
 	}
 }
 
+/// The session context of a runner exec.
+pub struct ExecContext<'a> {
+	/// The Awesome.toml dir (the root of the built-in action paths).
+	pub root_dir: &'a Path,
+	/// The session deadline (from `--timeout`).
+	pub deadline: Option<Instant>,
+	/// The sequential processes being awaited, terminated when the session gets interrupted.
+	pub in_flight: &'a InFlight,
+	/// The session vars (e.g., from `capture`), added to the process env, and replacing the `{{NAME}}`
+	/// in the cmd, args, and working_dir.
+	pub vars: &'a Vars,
+	/// The stdout of the session runners read by other runners (`stdin = { from = "..." }`), by run ref.
+	pub outputs: &'a RefCell<HashMap<String, StdoutBuffer>>,
+	/// The `for_each` items (batch) of this exec, empty when not a for_each runner.
+	pub items: &'a [String],
+}
+
+/// Render an arg with the vars. When batched (more than one item), an arg with `{{item}}` gets repeated
+/// for each item.
+fn render_arg(arg: &str, vars: &Vars, items: &[String]) -> Vec<String> {
+	if items.len() <= 1 || !has_template_var(arg, ITEM_VAR) {
+		return vec![render_template(arg, vars)];
+	}
+	items
+		.iter()
+		.map(|item| {
+			let item_vars = Vars::from([(ITEM_VAR.to_string(), item.to_string())]);
+			render_template(&render_template(arg, &item_vars), vars)
+		})
+		.collect()
+}
+
+pub enum ExecOutput {
+	/// The sequential runner completed successfully.
+	Done,
+	/// The (trimmed and extracted) output of a `capture` runner.
+	Captured(String),
+	/// The child of a concurrent runner.
+	Spawned(Box<RunnerChild>),
+}
+
+/// A spawned concurrent runner process.
+pub struct RunnerChild {
+	pub child: Child,
+	/// The output processing tasks, to be awaited after the child exited (so that all of its lines got checked).
+	pub pumps: Vec<JoinHandle<()>>,
+	/// The `ready_when.output` matcher (None when no output probe).
+	ready_output: Option<LineMatcher>,
+	/// To be checked when the process exits.
+	pub checks: OutputChecks,
+}
+
+/// The `fail_on_output` and `expect_output` matchers of a runner process.
+#[derive(Clone, Default)]
+pub struct OutputChecks {
+	pub fail_on: Option<LineMatcher>,
+	pub expect: Option<LineMatcher>,
+}
+
 pub enum ShouldRun {
 	Yes,
 	No(String), // reason
@@ -1,4 +1,5 @@
 use std::process::ExitStatus;
+use std::time::Duration;
 use toml::Value;
 
 pub type Result<R> = std::result::Result<R, Error>;
@@ -12,6 +13,15 @@ pub enum Error {
 	#[error("Fail to execute {0} cause: {1}")]
 	Exec(String, String),
 
+	#[error("Fail to execute {0} - {1}")]
+	ExecStatus(String, ExitStatus),
+
+	#[error("Timeout - {0} did not complete within {}", crate::summary::format_duration(*.1))]
+	ExecTimeout(String, Duration),
+
+	#[error("No runners found for '{0}'.{1}")]
+	RunRefNotFound(String, String),
+
 	#[error("'Awesome.toml' file not found. Should be added where 'awesome` command get called.")]
 	AwesomTomlNotFound,
 
@@ -24,6 +34,36 @@ pub enum Error {
 	#[error("Path not safe to delete {0}")]
 	PathNotSafeToDelete(String),
 
+	#[error("Runner '{0}' has no 'cmd', 'action', nor 'wait_for'")]
+	RunnerHasNoCmdOrAction(String),
+
+	#[error("Runner '{0}' has an invalid action - {1}")]
+	InvalidAction(String, String),
+
+	#[error("Action '{0}' failed. Cause: {1}")]
+	ActionFailed(String, String),
+
+	#[error("Runner '{0}' has an invalid input '{1}' - {2}")]
+	InvalidInputDef(String, String, String),
+
+	#[error("Input '{0}' is required. Use `--input {0}=VALUE` (or run in a terminal to be prompted).")]
+	InputMissing(String),
+
+	#[error("Input '{0}' invalid - {1}")]
+	InputInvalid(String, String),
+
+	#[error("Unexpected argument '{0}'. All of the inputs already have a value.")]
+	InputUnexpectedArg(String),
+
+	#[error("Runner '{0}' not confirmed. Aborting.")]
+	ConfirmAborted(String),
+
+	#[error("Runner '{0}' has an invalid wait_for - {1}")]
+	InvalidWaitFor(String, String),
+
+	#[error("Runner '{0}' - {1} not ready after {}", crate::summary::format_duration(*.2))]
+	WaitForTimeout(String, String, Duration),
+
 	#[error("Still have some unresolved fefed Runners. {0}")]
 	StillHaveUnresolvedRefedRunners(Value),
 
@@ -39,12 +79,72 @@ pub enum Error {
 	#[error("Fail to parse runner. Cause: {0}")]
 	FailParsingRunner(toml::de::Error),
 
+	#[error("Runner '{0}' has an invalid matrix - {1}")]
+	InvalidMatrix(String, String),
+
+	#[error("Fail to parse [output]. Cause: {0}")]
+	FailParsingOutputConfig(toml::de::Error),
+
+	#[error("Fail to parse [logs]. Cause: {0}")]
+	FailParsingLogsConfig(toml::de::Error),
+
+	#[error("Fail to parse [hooks]. Cause: {0}")]
+	FailParsingHooksConfig(toml::de::Error),
+
+	#[error("No log files found for '{0}' (in '{1}')")]
+	NoLogsFound(String, String),
+
+	#[error("'default' should be a run ref string. Was: {0}")]
+	InvalidDefaultRef(Value),
+
+	#[error("'{0}' is reserved for the 'awesome {0}' command, rename the group or runner")]
+	ReservedRunRef(String),
+
+	#[error("Shell '{0}' not supported for completions")]
+	ShellNotSupported(String),
+
+	#[error("Runner picker failed. Cause: {0}")]
+	PickerFailed(String),
+
 	#[error("Solo runner '{0}' defined multiple time")]
 	SoloRunnerMultipleDef(String),
 
 	#[error("Awesome.toml does not seem to be valid.")]
 	AwesomeTomlInvalid,
 
+	#[error("Runner '{0}' not ready after {} (ready_when.timeout).", crate::summary::format_duration(*.1))]
+	RunnerReadyTimeout(String, Duration),
+
+	#[error("Runner '{0}' exited before being ready. Exit status: {1}")]
+	RunnerExitedBeforeReady(String, String),
+
+	#[error("Output line matched fail_on_output '{0}': \"{1}\"")]
+	OutputFailMatched(String, String),
+
+	#[error("No output line matched expect_output '{0}'")]
+	OutputNotExpected(String),
+
+	#[error("Capture '{0}' - no value found for {1}")]
+	CaptureNotFound(String, String),
+
+	#[error("Capture '{0}' - output is not valid json. Cause: {1}")]
+	CaptureInvalidJson(String, String),
+
+	#[error("Invalid regex '{0}'. Cause: {1}")]
+	InvalidRegex(String, String),
+
+	#[error("Runner '{0}' has an invalid for_each - {1}")]
+	ForEachInvalid(String, String),
+
+	#[error("{0}/{1} for_each items failed. First '{2}' - {3}")]
+	ForEachFailed(usize, usize, String, Box<Error>),
+
+	#[error("Cannot open '{0}' for the runner stdio. Cause: {1}")]
+	StdioFile(String, String),
+
+	#[error("Runner '{0}' stdin from '{1}' - no such runner ran (yet) in this session")]
+	StdinFromNotRun(String, String),
+
 	#[error("Fail to read line")]
 	StdinFailToReadLine,
 
@@ -56,6 +156,6 @@ type ExecWithExitStatus<'a> = (&'a str, &'a [&'a str], ExitStatus);
 
 impl<'a> From<ExecWithExitStatus<'a>> for Error {
 	fn from(val: ExecWithExitStatus) -> Self {
-		Error::Exec(val.0.to_string(), "".to_string())
+		Error::ExecStatus(format!("{} {}", val.0, val.1.join(" ")), val.2)
 	}
 }
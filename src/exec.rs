@@ -1,10 +1,22 @@
+use crate::config::StopSignal;
+use crate::output::{pipe_child_output, OutputPipe};
+use crate::proc_tree::terminate_process_tree;
+use crate::utils::Vars;
 use crate::{Error, Result};
+use futures::future::join_all;
+use std::fs::{self, File};
 use std::io::{self, stdin, Write};
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 use tokio::process::{Child as TokioChild, Command as TokioCommand};
+use tokio::sync::{oneshot, Mutex};
+use tokio::time::timeout_at;
 
-#[allow(unused)]
 pub fn prompt(message: &str, default: Option<&str>) -> Result<String> {
 	print!("{message}");
 	let _ = io::stdout().flush();
@@ -12,6 +24,40 @@ pub fn prompt(message: &str, default: Option<&str>) -> Result<String> {
 	let mut buf = String::new();
 	stdin().read_line(&mut buf).map_err(|_| Error::StdinFailToReadLine)?;
 
+	Ok(answer_or_default(&buf, default))
+}
+
+/// The stdin line being read for `prompt_async` (on a thread, as reading stdin blocks).
+/// Note: Kept when the prompt gets dropped (e.g., interrupted), as the thread cannot be stopped,
+///       so that its line goes to the next prompt rather than getting lost.
+static PENDING_LINE: Mutex<Option<oneshot::Receiver<io::Result<String>>>> = Mutex::const_new(None);
+
+/// Same as `prompt`, but reads stdin from a thread, so that the session can still be interrupted
+/// (e.g., Ctrl-C) while waiting for the answer.
+/// Note: The thread of an interrupted prompt still reads the next line typed, which goes to the next prompt
+///       (a runner reading the terminal stdin in the meantime, e.g., a finally hook, does not get it).
+pub async fn prompt_async(message: &str, default: Option<&str>) -> Result<String> {
+	print!("{message}");
+	let _ = io::stdout().flush();
+
+	let mut pending = PENDING_LINE.lock().await;
+	let line = pending.get_or_insert_with(|| {
+		let (tx, rx) = oneshot::channel();
+		thread::spawn(move || {
+			let mut buf = String::new();
+			let _ = tx.send(stdin().read_line(&mut buf).map(|_| buf));
+		});
+		rx
+	});
+	let line = line.await;
+	*pending = None;
+
+	let buf = line.ok().and_then(|line| line.ok()).ok_or(Error::StdinFailToReadLine)?;
+	Ok(answer_or_default(&buf, default))
+}
+
+/// The trimmed answer, or the default (if any) when empty.
+fn answer_or_default(buf: &str, default: Option<&str>) -> String {
 	let val = buf.trim();
 
 	let val = match (val.is_empty(), default) {
@@ -20,77 +66,310 @@ pub fn prompt(message: &str, default: Option<&str>) -> Result<String> {
 		(true, None) => val, // return the empty string (TODO: might want to return error)
 	};
 
-	Ok(val.to_string())
+	val.to_string()
 }
 
-pub fn spawn_and_wait(cwd: Option<&Path>, cmd_str: &str, args: &[&str], print_exec: bool) -> Result<()> {
-	let mut cmd = build_cmd(cwd, cmd_str, args);
+/// How to terminate a process (and its children), and when.
+pub struct Stop {
+	/// The runner name (for the messages).
+	pub name: String,
+	/// The deadline (e.g., runner `timeout`), if any.
+	pub at: Option<Instant>,
+	pub stop_signal: StopSignal,
+	pub stop_timeout: Duration,
+	/// Where the process is tracked while awaited, to be terminated when its wait gets dropped.
+	pub in_flight: InFlight,
+}
 
-	if print_exec {
-		println!("> executing: {} {}", cmd_str, args.join(" "));
+/// The sequential processes being awaited by `spawn_and_wait`. As they are not killed on drop, the ones whose
+/// wait got dropped (e.g., the session start on Ctrl-C) stay here, to be terminated with `terminate_all`.
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<StdMutex<Vec<InFlightProc>>>);
+
+struct InFlightProc {
+	name: String,
+	stop_signal: StopSignal,
+	stop_timeout: Duration,
+	child: Arc<Mutex<TokioChild>>,
+}
+
+impl InFlight {
+	fn add(&self, stop: &Stop, child: &Arc<Mutex<TokioChild>>) {
+		if let Ok(mut procs) = self.0.lock() {
+			procs.push(InFlightProc {
+				name: stop.name.to_string(),
+				stop_signal: stop.stop_signal,
+				stop_timeout: stop.stop_timeout,
+				child: child.clone(),
+			});
+		}
+	}
+
+	fn remove(&self, child: &Arc<Mutex<TokioChild>>) {
+		if let Ok(mut procs) = self.0.lock() {
+			procs.retain(|proc| !Arc::ptr_eq(&proc.child, child));
+		}
+	}
+
+	/// Terminate the processes left in flight (with their `stop_signal` and `stop_timeout`),
+	/// and wait for them to exit.
+	pub async fn terminate_all(&self) {
+		let procs = self.0.lock().map(|mut procs| mem::take(&mut *procs)).unwrap_or_default();
+		join_all(procs.into_iter().map(|proc| async move {
+			let mut child = proc.child.lock().await;
+			terminate_process_tree(&proc.name, &mut child, proc.stop_signal, proc.stop_timeout).await;
+			let _ = child.wait().await;
+		}))
+		.await;
+	}
+}
+
+/// Spawn and wait (async) for the command. When `output_pipe` is given, stdout/stderr are piped through it
+/// (`spawn_options.pipe_output` being set accordingly).
+/// When the `stop.at` deadline is reached, the process tree gets terminated, and an `Error::ExecTimeout` is returned.
+/// Returns the exit status (the caller decides if it is a success).
+pub async fn spawn_and_wait(
+	cwd: Option<&Path>,
+	cmd_str: &str,
+	args: &[&str],
+	print_exec: bool,
+	output_pipe: Option<OutputPipe>,
+	stop: Stop,
+	spawn_options: SpawnOptions,
+) -> Result<ExitStatus> {
+	let start = Instant::now();
+	let spawn_options = SpawnOptions {
+		pipe_output: output_pipe.is_some(),
+		..spawn_options
+	};
+	let mut child = spawn_tokio(cwd, cmd_str, args, print_exec, spawn_options)?;
+	let pumps = output_pipe
+		.map(|output_pipe| pipe_child_output(&mut child, output_pipe))
+		.unwrap_or_default();
+
+	// Note: Tracked while awaited, removed once exited (so stays tracked if this future gets dropped).
+	let child = Arc::new(Mutex::new(child));
+	stop.in_flight.add(&stop, &child);
+	let mut timed_out = false;
+	let status = {
+		let mut child = child.lock().await;
+		match stop.at {
+			Some(at) => match timeout_at(at.into(), child.wait()).await {
+				Ok(status) => status?,
+				Err(_) => {
+					timed_out = true;
+					terminate_process_tree(&stop.name, &mut child, stop.stop_signal, stop.stop_timeout).await;
+					child.wait().await?
+				}
+			},
+			None => child.wait().await?,
+		}
+	};
+	stop.in_flight.remove(&child);
+	// Make sure all of the lines got processed before returning.
+	for pump in pumps {
+		let _ = pump.await;
+	}
+
+	if timed_out {
+		Err(Error::ExecTimeout(
+			format!("{cmd_str} {}", args.join(" ")),
+			start.elapsed(),
+		))
+	} else {
+		Ok(status)
+	}
+}
+
+/// How to spawn a (tokio) process.
+#[derive(Default)]
+pub struct SpawnOptions {
+	/// Pipe stdout/stderr (to be processed with `output::pipe_child_output`),
+	/// except the ones redirected to a file by `stdio`.
+	pub pipe_output: bool,
+	/// Spawn in its own process group (on unix), detached from the terminal stdin,
+	/// so that the whole process tree can be signaled (e.g., concurrent runners).
+	pub own_process_group: bool,
+	/// Environment variables added to the process environment.
+	pub env: Vars,
+	pub stdio: StdioOptions,
+}
+
+// region:    --- Stdio
+
+/// Where the stdin of a process comes from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum StdinSource {
+	#[default]
+	Inherit,
+	Null,
+	File(PathBuf),
+	/// Written to the process stdin, which then gets closed.
+	Text(String),
+}
+
+/// The stdin of a process, and the files its stdout/stderr get redirected to (instead of inherited or piped).
+#[derive(Debug, Default, Clone)]
+pub struct StdioOptions {
+	pub stdin: StdinSource,
+	pub stdout: Option<PathBuf>,
+	pub stderr: Option<PathBuf>,
+}
+
+impl StdioOptions {
+	fn stdin(&self) -> Result<Stdio> {
+		Ok(match &self.stdin {
+			StdinSource::Inherit => Stdio::inherit(),
+			StdinSource::Null => Stdio::null(),
+			StdinSource::File(path) => File::open(path).map_err(|ex| stdio_file_error(path, ex))?.into(),
+			StdinSource::Text(_) => Stdio::piped(),
+		})
+	}
+
+	/// The stdout/stderr redirect files (created, or truncated), if any.
+	fn outputs(&self) -> Result<(Option<Stdio>, Option<Stdio>)> {
+		Ok((
+			create_output_file(self.stdout.as_deref())?,
+			create_output_file(self.stderr.as_deref())?,
+		))
 	}
 
-	match cmd.spawn()?.wait() {
-		Ok(status) => {
-			if !status.success() {
-				Err((cmd_str, args, status).into())
-			} else {
-				Ok(())
-			}
+	/// The text to write to the stdin (once spawned), if any.
+	fn stdin_text(&self) -> Option<String> {
+		match &self.stdin {
+			StdinSource::Text(text) => Some(text.to_string()),
+			_ => None,
 		}
-		Err(ex) => Err(ex.into()),
 	}
 }
 
-pub fn spawn_tokio(cwd: Option<&Path>, cmd_str: &str, args: &[&str], print_exec: bool) -> Result<TokioChild> {
+fn create_output_file(path: Option<&Path>) -> Result<Option<Stdio>> {
+	let Some(path) = path else {
+		return Ok(None);
+	};
+	if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+		fs::create_dir_all(parent).map_err(|ex| stdio_file_error(path, ex))?;
+	}
+	let file = File::create(path).map_err(|ex| stdio_file_error(path, ex))?;
+	Ok(Some(file.into()))
+}
+
+fn stdio_file_error(path: &Path, ex: io::Error) -> Error {
+	Error::StdioFile(path.to_string_lossy().to_string(), ex.to_string())
+}
+
+// endregion: --- Stdio
+
+pub fn spawn_tokio(
+	cwd: Option<&Path>,
+	cmd_str: &str,
+	args: &[&str],
+	print_exec: bool,
+	options: SpawnOptions,
+) -> Result<TokioChild> {
 	if print_exec {
 		println!("> executing: {} {}", cmd_str, args.join(" "));
 	}
-	let mut cmd = build_tokio_cmd(cwd, cmd_str, args);
+	let mut cmd = build_tokio_cmd(cwd, cmd_str, args, &options.stdio)?;
+	cmd.envs(&options.env);
+	if options.pipe_output {
+		if options.stdio.stdout.is_none() {
+			cmd.stdout(Stdio::piped());
+		}
+		if options.stdio.stderr.is_none() {
+			cmd.stderr(Stdio::piped());
+		}
+	}
+	if options.own_process_group {
+		// Note: Not in the terminal foreground process group anymore, so it cannot read the terminal.
+		if options.stdio.stdin == StdinSource::Inherit {
+			cmd.stdin(Stdio::null());
+		}
+		#[cfg(unix)]
+		cmd.process_group(0);
+	}
 
-	let child = cmd.spawn()?;
+	let mut child = cmd.spawn()?;
+
+	if let (Some(text), Some(mut stdin)) = (options.stdio.stdin_text(), child.stdin.take()) {
+		// Note: The stdin gets closed (dropped) once written, so the process sees the end of the input.
+		tokio::spawn(async move {
+			let _ = stdin.write_all(text.as_bytes()).await;
+		});
+	}
 
 	Ok(child)
 }
 
-pub fn build_cmd(cwd: Option<&Path>, cmd: &str, args: &[&str]) -> Command {
+pub fn build_cmd(cwd: Option<&Path>, cmd: &str, args: &[&str], stdio: &StdioOptions) -> Result<Command> {
 	let mut cmd = Command::new(cmd);
 	if let Some(cwd) = cwd {
 		cmd.current_dir(cwd);
 	}
 	cmd.args(args);
-	cmd
+	cmd.stdin(stdio.stdin()?);
+	let (stdout, stderr) = stdio.outputs()?;
+	if let Some(stdout) = stdout {
+		cmd.stdout(stdout);
+	}
+	if let Some(stderr) = stderr {
+		cmd.stderr(stderr);
+	}
+	Ok(cmd)
 }
 
-pub fn build_tokio_cmd(cwd: Option<&Path>, cmd: &str, args: &[&str]) -> TokioCommand {
+pub fn build_tokio_cmd(cwd: Option<&Path>, cmd: &str, args: &[&str], stdio: &StdioOptions) -> Result<TokioCommand> {
 	let mut cmd = TokioCommand::new(cmd);
 	if let Some(cwd) = cwd {
 		cmd.current_dir(cwd);
 	}
 	cmd.args(args);
-	cmd
+	cmd.stdin(stdio.stdin()?);
+	let (stdout, stderr) = stdio.outputs()?;
+	if let Some(stdout) = stdout {
+		cmd.stdout(stdout);
+	}
+	if let Some(stderr) = stderr {
+		cmd.stderr(stderr);
+	}
+	Ok(cmd)
 }
 
-#[allow(unused)]
-pub fn spawn_output(cwd: Option<&Path>, cmd_str: &str, args: &[&str], print_exec: bool) -> Result<String> {
+/// Run the command to completion, and returns its stdout (stderr is inherited, unless redirected).
+/// Note: The stdout is always captured (`options.stdio.stdout` is ignored).
+pub fn spawn_output(
+	cwd: Option<&Path>,
+	cmd_str: &str,
+	args: &[&str],
+	print_exec: bool,
+	options: &SpawnOptions,
+) -> Result<String> {
 	if print_exec {
 		println!("> executing: {} {}", cmd_str, args.join(" "));
 	}
-	let mut cmd = build_cmd(cwd, cmd_str, args);
-
-	match cmd.stdout(Stdio::piped()).output() {
-		Err(ex) => Err(ex.into()),
-		Ok(output) => {
-			let txt = if output.status.success() {
-				String::from_utf8(output.stdout)
-			} else {
-				String::from_utf8(output.stderr)
-			};
-
-			match txt {
-				Err(ex) => Err(Error::Exec(cmd_str.to_string(), format!("{ex:?}"))),
-				Ok(txt) => Ok(txt),
-			}
-		}
+	let stdio = StdioOptions {
+		stdout: None,
+		..options.stdio.clone()
+	};
+	let mut cmd = build_cmd(cwd, cmd_str, args, &stdio)?;
+	cmd.envs(&options.env);
+
+	let mut child = cmd.stdout(Stdio::piped()).spawn()?;
+	if let (Some(text), Some(mut stdin)) = (stdio.stdin_text(), child.stdin.take()) {
+		std::thread::spawn(move || {
+			let _ = stdin.write_all(text.as_bytes());
+		});
+	}
+	let output = child.wait_with_output()?;
+	if !output.status.success() {
+		return Err((cmd_str, args, output.status).into());
 	}
+
+	String::from_utf8(output.stdout).map_err(|ex| Error::Exec(cmd_str.to_string(), format!("{ex:?}")))
 }
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_exec.rs"]
+mod tests;
+// endregion: --- Tests
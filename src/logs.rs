@@ -0,0 +1,227 @@
+//! Runner log files (`.awesome/logs/<group>/<runner>.log`) and the `awesome logs` command.
+
+use crate::config::{find_and_parse_awesome_toml, LogsConfig};
+use crate::{Error, Result};
+use regex::Regex;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::Duration;
+
+const LOG_EXT: &str = "log";
+const FOLLOW_POLL_DELAY: u64 = 250; // in ms
+
+// region:    --- RunnerLog
+
+/// The log file of a runner, shared by all of its output pipes of the session (e.g., for_each batches, retries),
+/// so that one `size` decides the rotation.
+pub type SharedLog = Arc<Mutex<RunnerLog>>;
+
+/// The log file of a runner, rotated when reaching the `max_size`.
+pub struct RunnerLog {
+	path: PathBuf,
+	file: File,
+	size: u64,
+	max_size: u64,
+	max_files: usize,
+}
+
+impl RunnerLog {
+	/// Open (append) the log file of a runner, and write the session header.
+	pub fn open(root_dir: &Path, logs_config: &LogsConfig, group: Option<&str>, name: &str) -> Result<Self> {
+		let path = runner_log_path(&root_dir.join(&logs_config.dir), group, name);
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+
+		let file = OpenOptions::new().create(true).append(true).open(&path)?;
+		let size = file.metadata()?.len();
+
+		let mut log = RunnerLog {
+			path,
+			file,
+			size,
+			max_size: logs_config.max_size,
+			max_files: logs_config.max_files,
+		};
+		log.write_line(&format!("==== {} - runner '{name}'", now_str()))?;
+
+		Ok(log)
+	}
+
+	pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+		if self.size >= self.max_size {
+			self.rotate()?;
+		}
+		let line = format!("{line}\n");
+		self.file.write_all(line.as_bytes())?;
+		self.size += line.len() as u64;
+		Ok(())
+	}
+
+	/// Rename `x.log.N-1` to `x.log.N` ... `x.log` to `x.log.1`, and start a new `x.log`.
+	fn rotate(&mut self) -> io::Result<()> {
+		for idx in (1..self.max_files).rev() {
+			let from = rotated_path(&self.path, idx);
+			if from.exists() {
+				fs::rename(&from, rotated_path(&self.path, idx + 1))?;
+			}
+		}
+		if self.max_files > 0 {
+			fs::rename(&self.path, rotated_path(&self.path, 1))?;
+		}
+
+		self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+		self.size = 0;
+		Ok(())
+	}
+}
+
+fn runner_log_path(logs_dir: &Path, group: Option<&str>, name: &str) -> PathBuf {
+	let file_name = format!("{name}.{LOG_EXT}");
+	match group {
+		Some(group) => logs_dir.join(group).join(file_name),
+		None => logs_dir.join(file_name),
+	}
+}
+
+fn rotated_path(path: &Path, idx: usize) -> PathBuf {
+	let mut path = path.as_os_str().to_owned();
+	path.push(format!(".{idx}"));
+	PathBuf::from(path)
+}
+
+fn now_str() -> String {
+	chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// endregion: --- RunnerLog
+
+// region:    --- Logs Command
+
+pub struct LogsOptions {
+	pub follow: bool,
+	pub tail: Option<usize>,
+	pub grep: Option<String>,
+}
+
+/// Print the log files for a run ref (`group`, `group.runner`, or `solo_runner`).
+pub fn show_logs(log_ref: &str, options: LogsOptions) -> Result<()> {
+	let root_dir = Path::new("./");
+	let config = find_and_parse_awesome_toml(root_dir)?;
+	let logs_dir = root_dir.join(&config.logs.dir);
+
+	let log_files = find_log_files(&logs_dir, log_ref)?;
+	if log_files.is_empty() {
+		return Err(Error::NoLogsFound(
+			log_ref.to_string(),
+			logs_dir.to_string_lossy().to_string(),
+		));
+	}
+
+	let grep = match options.grep.as_deref() {
+		Some(pattern) => {
+			Some(Regex::new(pattern).map_err(|ex| Error::InvalidRegex(pattern.to_string(), ex.to_string()))?)
+		}
+		None => None,
+	};
+
+	// Note: When more than one file (i.e., group), lines get prefixed by the runner name.
+	let multi = log_files.len() > 1;
+	let print_line = |name: &str, line: &str| {
+		if grep.as_ref().is_none_or(|re| re.is_match(line)) {
+			if multi {
+				println!("[{name}] {line}");
+			} else {
+				println!("{line}");
+			}
+		}
+	};
+
+	// -- Print the current content.
+	let mut followed: Vec<(String, PathBuf, u64)> = Vec::new();
+	for (name, path) in log_files {
+		let content = fs::read_to_string(&path)?;
+		let lines: Vec<&str> = content.lines().collect();
+		let skip = options.tail.map(|n| lines.len().saturating_sub(n)).unwrap_or(0);
+		for line in &lines[skip..] {
+			print_line(&name, line);
+		}
+		followed.push((name, path, content.len() as u64));
+	}
+
+	// -- Follow the new content (polling).
+	if options.follow {
+		loop {
+			for (name, path, pos) in followed.iter_mut() {
+				let Ok(mut file) = File::open(&path) else {
+					continue;
+				};
+				let len = file.metadata()?.len();
+				// file got rotated, restart from the beginning
+				if len < *pos {
+					*pos = 0;
+				}
+				if len > *pos {
+					file.seek(SeekFrom::Start(*pos))?;
+					let mut buf = String::new();
+					file.read_to_string(&mut buf)?;
+					// only consume the complete lines
+					let complete = buf.rfind('\n').map(|i| i + 1).unwrap_or(0);
+					for line in buf[..complete].lines() {
+						print_line(name, line);
+					}
+					*pos += complete as u64;
+				}
+			}
+			sleep(Duration::from_millis(FOLLOW_POLL_DELAY));
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns the `(runner_name, log_path)` for a log ref.
+/// - `group.runner` - the runner log of this group.
+/// - `name` - all of the runner logs of the group `name`, or the solo runner `name` log.
+fn find_log_files(logs_dir: &Path, log_ref: &str) -> Result<Vec<(String, PathBuf)>> {
+	if let Some((group, name)) = log_ref.split_once('.') {
+		let path = runner_log_path(logs_dir, Some(group), name);
+		return Ok(if path.is_file() {
+			vec![(name.to_string(), path)]
+		} else {
+			Vec::new()
+		});
+	}
+
+	let group_dir = logs_dir.join(log_ref);
+	if group_dir.is_dir() {
+		let mut files: Vec<(String, PathBuf)> = fs::read_dir(&group_dir)?
+			.filter_map(|entry| entry.ok().map(|e| e.path()))
+			.filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == LOG_EXT))
+			.filter_map(|path| {
+				let name = path.file_stem()?.to_string_lossy().to_string();
+				Some((name, path))
+			})
+			.collect();
+		files.sort();
+		return Ok(files);
+	}
+
+	let path = runner_log_path(logs_dir, None, log_ref);
+	Ok(if path.is_file() {
+		vec![(log_ref.to_string(), path)]
+	} else {
+		Vec::new()
+	})
+}
+
+// endregion: --- Logs Command
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_logs.rs"]
+mod tests;
+// endregion: --- Tests
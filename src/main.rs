@@ -1,35 +1,101 @@
 use app_cmd::app_cmd;
-use run_runners::run;
+use clap_complete::CompleteEnv;
+use completions::{print_completions, COMPLETE_VAR};
+use logs::{show_logs, LogsOptions};
+use picker::{default_or_picked_ref, Picked};
+use run_runners::{run, RunOptions};
+use std::time::Duration;
 
+mod actions;
 mod app_cmd;
+mod completions;
 mod config;
 mod error;
 mod exec;
+mod logs;
+mod output;
+mod picker;
+mod probe;
+mod proc_tree;
 mod run_runners;
+mod summary;
+mod supervisor;
 mod utils;
 
+#[cfg(all(test, unix))]
+#[path = "_tests/support.rs"]
+mod test_support;
+
 pub use self::error::{Error, Result};
 
 pub use app_cmd::VERSION;
 
 fn main() {
+	// Note: When called back by the shell completion script (`COMPLETE=bash awesome -- ...`), complete and exit.
+	CompleteEnv::with_factory(app_cmd).var(COMPLETE_VAR).complete();
+
 	match cmd_run() {
-		Ok(_) => (),
-		Err(err) => println!("FAIL - {err}"),
+		Ok(exit_code) => std::process::exit(exit_code),
+		Err(err) => {
+			println!("FAIL - {err}");
+			std::process::exit(1);
+		}
 	}
 }
 
-fn cmd_run() -> Result<()> {
+/// Run the command, and returns the process exit code.
+fn cmd_run() -> Result<i32> {
 	let app = app_cmd().get_matches();
-	let input = app.get_one::<String>("INPUT");
+
+	// -- The `awesome logs REF` subcommand
+	if let Some(("logs", sub)) = app.subcommand() {
+		// Note: REF is required, so clap guarantees it is present.
+		let log_ref = sub.get_one::<String>("REF").map(String::as_str).unwrap_or_default();
+		let options = LogsOptions {
+			follow: sub.get_flag("follow"),
+			tail: sub.get_one::<usize>("tail").copied(),
+			grep: sub.get_one::<String>("grep").cloned(),
+		};
+		show_logs(log_ref, options)?;
+		return Ok(0);
+	}
+
+	// -- The `awesome completions SHELL` subcommand
+	if let Some(("completions", sub)) = app.subcommand() {
+		// Note: SHELL is required, so clap guarantees it is present.
+		let shell = sub.get_one::<String>("SHELL").map(String::as_str).unwrap_or_default();
+		print_completions(shell)?;
+		return Ok(0);
+	}
+
+	// -- Without run ref, the config `default`, or picked by the user (when in a terminal).
+	let input = match app.get_one::<String>("INPUT") {
+		Some(input) => Some(input.to_string()),
+		None => match default_or_picked_ref()? {
+			Picked::Ref(run_ref) => Some(run_ref),
+			Picked::Cancelled => return Ok(0),
+			Picked::NotInteractive => None,
+		},
+	};
 
 	if let Some(input) = input {
-		run(input)?;
+		let options = RunOptions {
+			keep_going: app.get_flag("keep-going"),
+			timeout: app.get_one::<Duration>("timeout").copied(),
+			yes: app.get_flag("yes"),
+			inputs: app
+				.get_many::<(String, String)>("input")
+				.into_iter()
+				.flatten()
+				.cloned()
+				.collect(),
+			input_args: app.get_many::<String>("ARGS").into_iter().flatten().cloned().collect(),
+		};
+		run(&input, options)
 	} else {
 		// needs cmd_app version as the orginal got consumed by get_matches
 		app_cmd().print_long_help()?;
 		println!("\n");
+		Ok(0)
 	}
-
-	Ok(())
 }
@@ -0,0 +1,182 @@
+//! Line-buffered output of the piped runner processes, printed with a (colored) `[runner_name]` prefix,
+//! and written to the runner log file.
+
+use crate::config::{Color, OutputConfig};
+use crate::logs::SharedLog;
+use crate::{Error, Result};
+use regex::Regex;
+use std::io::IsTerminal;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+use tokio::task::JoinHandle;
+
+/// Print the lines of a runner with its prefix (and optional timestamp).
+pub struct LinePrinter {
+	prefix: String,
+	timestamp_format: Option<String>,
+}
+
+impl LinePrinter {
+	/// Printer without prefix nor timestamp (e.g., for the sequential runners).
+	pub fn plain() -> Self {
+		LinePrinter {
+			prefix: String::new(),
+			timestamp_format: None,
+		}
+	}
+
+	pub fn new(output_config: &OutputConfig, name: &str, color: Color) -> Self {
+		let prefix = output_config.prefix.replace("{name}", name);
+		let prefix = if output_config.color && color_enabled() {
+			format!("\x1b[{}m{prefix}\x1b[0m", color.ansi_code())
+		} else {
+			prefix
+		};
+		let timestamp_format = output_config.timestamps.then(|| output_config.timestamp_format.to_string());
+
+		LinePrinter {
+			prefix,
+			timestamp_format,
+		}
+	}
+
+	pub fn print(&self, line: &str, is_stderr: bool) {
+		let line = match (&self.timestamp_format, self.prefix.is_empty()) {
+			(Some(fmt), _) => format!("{} {} {line}", chrono::Local::now().format(fmt), self.prefix),
+			(None, false) => format!("{} {line}", self.prefix),
+			(None, true) => line.to_string(),
+		};
+		// Note: println!/eprintln! lock the stream for the whole line, so lines do not get interleaved.
+		if is_stderr {
+			eprintln!("{line}");
+		} else {
+			println!("{line}");
+		}
+	}
+}
+
+/// Record the first line of the output matching `re` (e.g., for the `ready_when.output` probe).
+/// Clones share the same state, so one can be given to the `OutputPipe`, and another one kept to check it.
+#[derive(Clone)]
+pub struct LineMatcher {
+	pub re: Regex,
+	first_match: Arc<OnceLock<String>>,
+}
+
+impl LineMatcher {
+	pub fn new(pattern: &str) -> Result<Self> {
+		let re = Regex::new(pattern).map_err(|ex| Error::InvalidRegex(pattern.to_string(), ex.to_string()))?;
+		Ok(LineMatcher {
+			re,
+			first_match: Arc::new(OnceLock::new()),
+		})
+	}
+
+	pub fn is_matched(&self) -> bool {
+		self.first_match.get().is_some()
+	}
+
+	pub fn first_match(&self) -> Option<&str> {
+		self.first_match.get().map(String::as_str)
+	}
+
+	fn on_line(&self, line: &str) {
+		if !self.is_matched() && self.re.is_match(line) {
+			let _ = self.first_match.set(line.to_string());
+		}
+	}
+}
+
+/// The stdout lines of a runner, kept for the runners reading it as their stdin (`stdin = { from = "..." }`).
+pub type StdoutBuffer = Arc<Mutex<String>>;
+
+/// What to do with each line of a piped child output.
+pub struct OutputPipe {
+	pub printer: LinePrinter,
+	pub log: Option<SharedLog>,
+	pub matchers: Vec<LineMatcher>,
+	pub stdout_buffer: Option<StdoutBuffer>,
+	/// Do not print the stdout lines (e.g., the `capture` runners), still logged, matched, and buffered.
+	pub quiet_stdout: bool,
+}
+
+impl OutputPipe {
+	pub fn new(printer: LinePrinter, log: Option<SharedLog>) -> Self {
+		OutputPipe {
+			printer,
+			log,
+			matchers: Vec::new(),
+			stdout_buffer: None,
+			quiet_stdout: false,
+		}
+	}
+
+	/// True when the lines need to be processed (prefixed, logged, matched, or buffered), and not only printed.
+	pub fn needs_lines(&self) -> bool {
+		!self.printer.prefix.is_empty()
+			|| self.log.is_some()
+			|| !self.matchers.is_empty()
+			|| self.stdout_buffer.is_some()
+	}
+
+	/// Write a line to the log file only (e.g., awesome notes like restarts).
+	pub fn write_log(&self, line: &str) {
+		if let Some(log) = &self.log {
+			if let Ok(mut log) = log.lock() {
+				// Note: A log write failure should not stop the runner.
+				let _ = log.write_line(line);
+			}
+		}
+	}
+
+	fn on_line(&self, line: &str, is_stderr: bool) {
+		if is_stderr || !self.quiet_stdout {
+			self.printer.print(line, is_stderr);
+		}
+		self.write_log(line);
+		for matcher in self.matchers.iter() {
+			matcher.on_line(line);
+		}
+		if let (Some(buffer), false) = (&self.stdout_buffer, is_stderr) {
+			if let Ok(mut buffer) = buffer.lock() {
+				buffer.push_str(line);
+				buffer.push('\n');
+			}
+		}
+	}
+}
+
+/// Take the piped stdout/stderr of the child and process them line by line in their own tasks.
+/// The returned handles complete when the streams get closed.
+pub fn pipe_child_output(child: &mut Child, output_pipe: OutputPipe) -> Vec<JoinHandle<()>> {
+	let output_pipe = Arc::new(output_pipe);
+	let mut pumps = Vec::new();
+	if let Some(stdout) = child.stdout.take() {
+		pumps.push(tokio::spawn(pump_lines(stdout, false, output_pipe.clone())));
+	}
+	if let Some(stderr) = child.stderr.take() {
+		pumps.push(tokio::spawn(pump_lines(stderr, true, output_pipe)));
+	}
+	pumps
+}
+
+async fn pump_lines(reader: impl AsyncRead + Unpin, is_stderr: bool, output_pipe: Arc<OutputPipe>) {
+	let mut reader = BufReader::new(reader);
+	let mut buf: Vec<u8> = Vec::new();
+	loop {
+		buf.clear();
+		match reader.read_until(b'\n', &mut buf).await {
+			Ok(0) | Err(_) => break,
+			Ok(_) => {
+				let line = String::from_utf8_lossy(&buf);
+				let line = line.trim_end_matches(['\n', '\r']);
+				output_pipe.on_line(line, is_stderr);
+			}
+		}
+	}
+}
+
+fn color_enabled() -> bool {
+	std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
@@ -0,0 +1,120 @@
+//! The run ref when `awesome` is run without one, the config `default`, or picked interactively.
+
+use crate::config::{find_and_parse_awesome_toml, Config, Runner};
+use crate::{Error, Result};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::FuzzySelect;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+/// A run ref of the picker, with its description.
+#[derive(Debug)]
+pub struct PickItem {
+	pub run_ref: String,
+	pub description: String,
+}
+
+pub enum Picked {
+	/// The config `default`, or the run ref picked by the user.
+	Ref(String),
+	/// The user cancelled the pick (Esc).
+	Cancelled,
+	/// No `Awesome.toml`, no runners, or not in a terminal.
+	NotInteractive,
+}
+
+/// The config `default` run ref, or the run ref picked by the user (when in a terminal).
+pub fn default_or_picked_ref() -> Result<Picked> {
+	let config = match find_and_parse_awesome_toml(Path::new("./")) {
+		Ok(config) => config,
+		Err(Error::AwesomTomlNotFound) => return Ok(Picked::NotInteractive),
+		Err(ex) => return Err(ex),
+	};
+
+	if let Some(default) = &config.default {
+		return Ok(Picked::Ref(default.to_string()));
+	}
+
+	if !io::stdout().is_terminal() || !io::stdin().is_terminal() {
+		return Ok(Picked::NotInteractive);
+	}
+
+	pick_run_ref(&config)
+}
+
+fn pick_run_ref(config: &Config) -> Result<Picked> {
+	let items = pick_items(config);
+	if items.is_empty() {
+		return Ok(Picked::NotInteractive);
+	}
+
+	let ref_width = items.iter().map(|item| item.run_ref.len()).max().unwrap_or(0);
+	let labels: Vec<String> = items
+		.iter()
+		.map(|item| {
+			format!("{:<ref_width$}  {}", item.run_ref, item.description)
+				.trim_end()
+				.to_string()
+		})
+		.collect();
+
+	let picked = FuzzySelect::with_theme(&ColorfulTheme::default())
+		.with_prompt("Run")
+		.items(&labels)
+		.default(0)
+		.interact_opt()
+		.map_err(|ex| Error::PickerFailed(ex.to_string()))?;
+
+	Ok(match picked {
+		Some(idx) => Picked::Ref(items[idx].run_ref.to_string()),
+		None => Picked::Cancelled,
+	})
+}
+
+/// Every group (followed by its runners), and then, every solo runner, by name.
+/// The runners expanded from a `matrix` are listed once, by their base name.
+pub fn pick_items(config: &Config) -> Vec<PickItem> {
+	let mut items = Vec::new();
+
+	let mut group_names: Vec<&String> = config.grouped_runners.keys().collect();
+	group_names.sort();
+	for group_name in group_names {
+		let runners = listed_runners(config.grouped_runners[group_name].iter());
+		let names: Vec<&str> = runners.iter().map(|r| runner_name(r)).collect();
+		items.push(PickItem {
+			run_ref: group_name.to_string(),
+			description: format!("runs {}", names.join(", ")),
+		});
+		items.extend(runners.iter().map(|runner| PickItem {
+			run_ref: format!("{group_name}.{}", runner_name(runner)),
+			description: runner.description.clone().unwrap_or_default(),
+		}));
+	}
+
+	let mut solo_runners = listed_runners(config.solo_runners.values());
+	solo_runners.sort_by_key(|runner| runner_name(runner));
+	items.extend(solo_runners.iter().map(|runner| PickItem {
+		run_ref: runner_name(runner).to_string(),
+		description: runner.description.clone().unwrap_or_default(),
+	}));
+
+	items
+}
+
+/// The runners without the matrix entries after the first one.
+fn listed_runners<'a>(runners: impl Iterator<Item = &'a Runner>) -> Vec<&'a Runner> {
+	runners
+		.filter(|runner| runner.matrix_entry.as_ref().is_none_or(|entry| entry.idx == 0))
+		.collect()
+}
+
+/// The runner name, before its matrix expansion.
+fn runner_name(runner: &Runner) -> &str {
+	runner.matrix_base_name().unwrap_or(&runner.name)
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_picker.rs"]
+mod tests;
+// endregion: --- Tests
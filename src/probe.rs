@@ -0,0 +1,84 @@
+//! Readiness probes (tcp port, file presence, http status) used to know when a runner is ready.
+
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+const TCP_CONNECT_TIMEOUT: u64 = 500; // in ms
+const HTTP_REQUEST_TIMEOUT: u64 = 2000; // in ms
+
+/// Returns true if a TCP connection can be established to `addr`.
+/// `addr` can be `host:port` or just a `port` (then, localhost is assumed).
+pub async fn tcp_accepts(addr: &str) -> bool {
+	let addr = if addr.chars().all(|c| c.is_ascii_digit()) {
+		format!("127.0.0.1:{addr}")
+	} else {
+		addr.to_string()
+	};
+
+	matches!(
+		timeout(Duration::from_millis(TCP_CONNECT_TIMEOUT), TcpStream::connect(addr)).await,
+		Ok(Ok(_))
+	)
+}
+
+/// Returns true if the file (or directory) exists.
+pub fn file_exists(path: &Path) -> bool {
+	path.exists()
+}
+
+/// The status code of a `GET` on a plain `http://` url, or None when the request failed
+/// (e.g., connection refused, invalid response, timeout).
+pub async fn http_status(url: &str) -> Option<u16> {
+	let (addr, path) = parse_http_url(url)?;
+
+	let request = async {
+		let mut stream = TcpStream::connect(&addr).await.ok()?;
+		let request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+		stream.write_all(request.as_bytes()).await.ok()?;
+
+		// -- Only the status line is needed, e.g., `HTTP/1.1 200 OK`.
+		let mut status_line = String::new();
+		BufReader::new(stream).read_line(&mut status_line).await.ok()?;
+		let mut parts = status_line.split_whitespace();
+		parts.next().filter(|version| version.starts_with("HTTP/"))?;
+		parts.next()?.parse().ok()
+	};
+
+	timeout(Duration::from_millis(HTTP_REQUEST_TIMEOUT), request)
+		.await
+		.ok()
+		.flatten()
+}
+
+/// The `host:port` (port 80 by default) and the path (with the query) of a plain `http://` url.
+pub fn parse_http_url(url: &str) -> Option<(String, String)> {
+	let rest = url.strip_prefix("http://")?;
+	// Note: The fragment is never sent.
+	let rest = rest.split('#').next().unwrap_or_default();
+	let (authority, path) = match rest.find(['/', '?']) {
+		Some(idx) if rest[idx..].starts_with('?') => (&rest[..idx], format!("/{}", &rest[idx..])),
+		Some(idx) => (&rest[..idx], rest[idx..].to_string()),
+		None => (rest, "/".to_string()),
+	};
+	if authority.is_empty() {
+		return None;
+	}
+
+	// Note: The `]` of an IPv6 host (e.g., `[::1]:8080`) is before the port.
+	let has_port = authority.rsplit(']').next().is_some_and(|end| end.contains(':'));
+	let addr = match has_port {
+		true => authority.to_string(),
+		false => format!("{authority}:80"),
+	};
+
+	Some((addr, path))
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_probe.rs"]
+mod tests;
+// endregion: --- Tests
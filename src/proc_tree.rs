@@ -0,0 +1,335 @@
+//! Termination of a runner process tree.
+//!
+//! On unix, each concurrent runner is spawned in its own process group, so the whole tree gets signaled at once.
+//! On Linux, the descendants that escaped the group (e.g., `setsid`) are also found from `/proc` (fallback).
+//! The runners not leading a group (e.g., sequential runners) get their descendants signaled one by one
+//! (from `/proc` on Linux, from `sysinfo` on the other unix platforms).
+//! On other platforms, the descendants are found from the `sysinfo` process table.
+
+use crate::config::StopSignal;
+use std::time::{Duration, Instant};
+use tokio::process::Child;
+use tokio::time::{sleep, timeout_at};
+
+const STOP_POLL_DELAY: u64 = 100; // in ms
+
+/// Terminate this process and all of its children.
+/// Sends the `stop_signal` to the whole tree, waits up to `stop_timeout`, and then kills (SIGKILL) the survivors.
+pub async fn terminate_process_tree(name: &str, proc: &mut Child, stop_signal: StopSignal, stop_timeout: Duration) {
+	let Some(proc_id) = proc.id() else {
+		return;
+	};
+	let deadline = Instant::now() + stop_timeout;
+	let tree = ProcTree::new(proc_id);
+
+	// --- Send the stop signal to the whole tree
+	tree.signal(stop_signal);
+
+	// --- Wait for the tree to exit (up to the stop_timeout)
+	let parent_exited = timeout_at(deadline.into(), proc.wait()).await.is_ok();
+	let mut tree_alive = tree.is_alive();
+	while tree_alive && Instant::now() < deadline {
+		sleep(Duration::from_millis(STOP_POLL_DELAY)).await;
+		tree_alive = tree.is_alive();
+	}
+
+	// --- Kill whatever survived
+	if !parent_exited || tree_alive {
+		println!(
+			"Runner '{name}' did not stop within {}ms after {stop_signal:?} signal. Killing it.",
+			stop_timeout.as_millis()
+		);
+		tree.signal(StopSignal::Kill);
+	}
+	if !parent_exited {
+		if let Err(ex) = proc.kill().await {
+			println!("Warning - error while stopping runner {name}. Cause: {ex}");
+		}
+	}
+}
+
+// region:    --- Unix (process group)
+
+#[cfg(unix)]
+use nix::sys::signal::{kill, killpg, Signal};
+#[cfg(unix)]
+use nix::unistd::{getpgid, Pid};
+
+/// The process group of the runner (pgid being the runner pid), plus the stragglers.
+/// When the runner is not a group leader (e.g., sequential runners), the runner and its descendants.
+#[cfg(unix)]
+struct ProcTree {
+	/// The process group, when the runner is its leader.
+	pgid: Option<Pid>,
+	/// Descendants not in the process group, or, without a group, the runner and all of its descendants
+	/// (found when the tree got created).
+	pids: Vec<Pid>,
+}
+
+#[cfg(unix)]
+impl ProcTree {
+	fn new(root_pid: u32) -> Self {
+		let root_pid = Pid::from_raw(root_pid as i32);
+		// Note: Never signal the group of a runner which is not its leader (e.g., the awesome group).
+		if getpgid(Some(root_pid)).is_ok_and(|pgid| pgid == root_pid) {
+			let pids = proc_fs::find_stragglers(root_pid);
+			ProcTree {
+				pgid: Some(root_pid),
+				pids,
+			}
+		} else {
+			let mut pids = proc_fs::find_descendants(root_pid);
+			pids.insert(0, root_pid);
+			ProcTree { pgid: None, pids }
+		}
+	}
+
+	fn signal(&self, stop_signal: StopSignal) {
+		let signal = unix_signal(stop_signal);
+		if let Some(pgid) = self.pgid {
+			let _ = killpg(pgid, signal);
+		}
+		for pid in self.pids.iter() {
+			let _ = kill(*pid, signal);
+		}
+	}
+
+	/// True if any process of the group (or of the pids) is still alive (zombies excluded).
+	fn is_alive(&self) -> bool {
+		self.pgid.is_some_and(proc_fs::is_group_alive) || self.pids.iter().any(|pid| proc_fs::is_alive(*pid))
+	}
+}
+
+#[cfg(unix)]
+fn unix_signal(stop_signal: StopSignal) -> Signal {
+	match stop_signal {
+		StopSignal::Int => Signal::SIGINT,
+		StopSignal::Term => Signal::SIGTERM,
+		StopSignal::Hup => Signal::SIGHUP,
+		StopSignal::Quit => Signal::SIGQUIT,
+		StopSignal::Kill => Signal::SIGKILL,
+	}
+}
+
+/// The `/proc` based fallback to find the descendants that escaped the process group.
+#[cfg(target_os = "linux")]
+mod proc_fs {
+	use nix::sys::signal::{kill, killpg};
+	use nix::unistd::Pid;
+	use std::collections::{HashMap, HashSet};
+	use std::fs;
+
+	/// `(ppid, pgid, state)` from `/proc/<pid>/stat`.
+	fn read_stat(pid: i32) -> Option<(i32, i32, char)> {
+		let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+		// Note: The process name (2nd field) is in parentheses and can contain spaces.
+		let after_name = &stat[stat.rfind(')')? + 1..];
+		let mut fields = after_name.split_whitespace();
+		let state = fields.next()?.chars().next()?;
+		let ppid = fields.next()?.parse().ok()?;
+		let pgid = fields.next()?.parse().ok()?;
+		Some((ppid, pgid, state))
+	}
+
+	/// The `(ppid, pgid)` of all processes.
+	fn load_procs() -> HashMap<i32, (i32, i32)> {
+		let Ok(entries) = fs::read_dir("/proc") else {
+			return HashMap::new();
+		};
+		entries
+			.flatten()
+			.filter_map(|entry| entry.file_name().to_str().and_then(|n| n.parse::<i32>().ok()))
+			.filter_map(|pid| read_stat(pid).map(|(ppid, pgid, _)| (pid, (ppid, pgid))))
+			.collect()
+	}
+
+	/// Add the descendants of the `tree` processes to the `tree`.
+	fn extend_with_descendants(procs: &HashMap<i32, (i32, i32)>, tree: &mut HashSet<i32>) {
+		loop {
+			let new_ones: Vec<i32> = procs
+				.iter()
+				.filter(|(pid, (ppid, _))| !tree.contains(pid) && tree.contains(ppid))
+				.map(|(pid, _)| *pid)
+				.collect();
+			if new_ones.is_empty() {
+				break;
+			}
+			tree.extend(new_ones);
+		}
+	}
+
+	/// Returns the descendants of the group leader which are not in its process group.
+	pub fn find_stragglers(pgid: Pid) -> Vec<Pid> {
+		let procs = load_procs();
+
+		// -- Collect the descendants of the group (the group members being the roots)
+		let root = pgid.as_raw();
+		let mut tree: HashSet<i32> = procs.iter().filter(|(_, (_, g))| *g == root).map(|(pid, _)| *pid).collect();
+		tree.insert(root);
+		extend_with_descendants(&procs, &mut tree);
+
+		tree.into_iter()
+			.filter(|pid| procs.get(pid).is_some_and(|(_, g)| *g != root))
+			.map(Pid::from_raw)
+			.collect()
+	}
+
+	/// Returns all of the descendants of the process.
+	pub fn find_descendants(pid: Pid) -> Vec<Pid> {
+		let procs = load_procs();
+		let mut tree = HashSet::from([pid.as_raw()]);
+		extend_with_descendants(&procs, &mut tree);
+		tree.remove(&pid.as_raw());
+		tree.into_iter().map(Pid::from_raw).collect()
+	}
+
+	/// True if the process exists and is not a zombie.
+	pub fn is_alive(pid: Pid) -> bool {
+		kill(pid, None).is_ok() && read_stat(pid.as_raw()).is_some_and(|(_, _, state)| state != 'Z')
+	}
+
+	/// True if any process of the group is not a zombie.
+	/// Note: `killpg(pgid, None)` succeeds as long as a member is not reaped (e.g., orphaned zombie),
+	///       so `/proc` only gets scanned to rule out the zombies when the group exists.
+	pub fn is_group_alive(pgid: Pid) -> bool {
+		if killpg(pgid, None).is_err() {
+			return false;
+		}
+		let Ok(entries) = fs::read_dir("/proc") else {
+			return true;
+		};
+		entries
+			.flatten()
+			.filter_map(|entry| entry.file_name().to_str().and_then(|n| n.parse::<i32>().ok()))
+			.filter_map(read_stat)
+			.any(|(_, g, state)| g == pgid.as_raw() && state != 'Z')
+	}
+}
+
+/// No `/proc` on the other unix platforms, the process group only (or the `sysinfo` descendants).
+#[cfg(all(unix, not(target_os = "linux")))]
+mod proc_fs {
+	use nix::sys::signal::{kill, killpg};
+	use nix::unistd::Pid;
+	use sysinfo::{ProcessRefreshKind, System};
+
+	pub fn find_stragglers(_pgid: Pid) -> Vec<Pid> {
+		Vec::new()
+	}
+
+	/// Returns all of the descendants of the process (from the `sysinfo` process table).
+	pub fn find_descendants(pid: Pid) -> Vec<Pid> {
+		let mut sys = System::new();
+		sys.refresh_processes_specifics(ProcessRefreshKind::new());
+		let root_pid = sysinfo::Pid::from_u32(pid.as_raw() as u32);
+		super::find_descendant(sys.processes(), &root_pid)
+			.into_iter()
+			.map(|pid| Pid::from_raw(pid.as_u32() as i32))
+			.collect()
+	}
+
+	pub fn is_alive(pid: Pid) -> bool {
+		kill(pid, None).is_ok()
+	}
+
+	pub fn is_group_alive(pgid: Pid) -> bool {
+		// Note: Signal `None` (i.e., 0) only checks for the existence.
+		killpg(pgid, None).is_ok()
+	}
+}
+
+// endregion: --- Unix (process group)
+
+// region:    --- Non Unix (sysinfo)
+
+#[cfg(not(target_os = "linux"))]
+use std::collections::HashMap;
+#[cfg(not(unix))]
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, Signal, System};
+
+/// The runner process and its descendants (found when the tree got created).
+#[cfg(not(unix))]
+struct ProcTree {
+	pids: Vec<Pid>,
+}
+
+#[cfg(not(unix))]
+impl ProcTree {
+	fn new(root_pid: u32) -> Self {
+		let root_pid = Pid::from_u32(root_pid);
+		let mut sys = System::new();
+		sys.refresh_processes_specifics(ProcessRefreshKind::new());
+		let mut pids = find_descendant(sys.processes(), &root_pid);
+		pids.insert(0, root_pid);
+		ProcTree { pids }
+	}
+
+	fn signal(&self, stop_signal: StopSignal) {
+		let mut sys = System::new();
+		sys.refresh_processes_specifics(ProcessRefreshKind::new());
+		for pid in self.pids.iter() {
+			if let Some(process) = sys.process(*pid) {
+				// Note: When the signal is not supported by the platform, kill right away.
+				if process.kill_with(sys_signal(stop_signal)).is_none() {
+					process.kill();
+				}
+			}
+		}
+	}
+
+	fn is_alive(&self) -> bool {
+		let mut sys = System::new();
+		self.pids.iter().any(|pid| {
+			sys.refresh_process_specifics(*pid, ProcessRefreshKind::new())
+				&& sys.process(*pid).is_some_and(|p| p.status() != ProcessStatus::Zombie)
+		})
+	}
+}
+
+#[cfg(not(unix))]
+fn sys_signal(stop_signal: StopSignal) -> Signal {
+	match stop_signal {
+		StopSignal::Int => Signal::Interrupt,
+		StopSignal::Term => Signal::Term,
+		StopSignal::Hup => Signal::Hangup,
+		StopSignal::Quit => Signal::Quit,
+		StopSignal::Kill => Signal::Kill,
+	}
+}
+
+/// Note: Also the fallback of the unix platforms without `/proc`, for the runners not leading a group.
+#[cfg(not(target_os = "linux"))]
+fn find_descendant(
+	sys_processes: &HashMap<sysinfo::Pid, sysinfo::Process>,
+	root_pid: &sysinfo::Pid,
+) -> Vec<sysinfo::Pid> {
+	let mut children: Vec<sysinfo::Pid> = Vec::new();
+
+	// NOTE: For now, going a little brute force, but this should be exhaustive
+	//       and does not really have significant performance impact for the usecase.
+	'main: loop {
+		let mut cycle_has = false;
+		for (pid, p) in sys_processes.iter() {
+			if let Some(parent_pid) = p.parent() {
+				if !children.contains(pid) && (parent_pid == *root_pid || children.contains(&parent_pid)) {
+					children.push(*pid);
+					cycle_has = true;
+				}
+			}
+		}
+		// if this cycle did not find anything, we can break the search.
+		if !cycle_has {
+			break 'main;
+		}
+	}
+
+	children
+}
+
+// endregion: --- Non Unix (sysinfo)
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_proc_tree.rs"]
+mod tests;
+// endregion: --- Tests
@@ -1,166 +1,987 @@
-use crate::config::{find_and_parse_awesome_toml, Runner, ShouldRun};
+use crate::config::{
+	find_and_parse_awesome_toml, resolve_inputs, Color, Config, ConfirmDefault, ExecContext, ExecOutput, ExitAction,
+	ForEach, HookKind, Hooks, OutputChecks, Runner, ShouldRun, Stdin,
+};
+use crate::exec::{prompt_async, InFlight};
+use crate::logs::{RunnerLog, SharedLog};
+use crate::output::{LinePrinter, OutputPipe, StdoutBuffer};
+use crate::summary::{format_duration, RunStatus, Summary};
+use crate::supervisor::{supervise, ExitEvent, Supervised};
+use crate::utils::Vars;
 use crate::{Error, Result};
-use std::collections::HashMap;
+use futures::stream::{self, StreamExt};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, IsTerminal};
 use std::path::Path;
-use std::time::Duration;
-use sysinfo::{Pid, Process, ProcessRefreshKind, System};
-use tokio::process::Child;
-use tokio::time::sleep;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::signal;
+use tokio::sync::{mpsc, Mutex, OwnedMutexGuard};
+use tokio::task::block_in_place;
+use tokio::time::{sleep, sleep_until, timeout_at};
 
-const WATCH_CHILD_DELAY: u64 = 3000; // in ms
+/// Exit code when the session got interrupted by Ctrl-C (128 + SIGINT).
+const CTRL_C_EXIT_CODE: i32 = 130;
 
-#[tokio::main]
-pub async fn run(run_ref: &str) -> Result<()> {
-	// -- Parse the command
-	let mut parts = run_ref.splitn(2, '.');
-	let part1 = parts.next().ok_or_else(|| Error::RunRefNoParts(run_ref.to_string()))?;
-	let part2 = parts.next();
+/// The run options from the command line.
+#[derive(Debug, Default)]
+pub struct RunOptions {
+	/// Keep running the next runners when a runner fails (like `continue_on_error` on all runners).
+	pub keep_going: bool,
+	/// Max duration of the whole session.
+	pub timeout: Option<Duration>,
+	/// Answer yes to the runner `confirm` questions.
+	pub yes: bool,
+	/// The runner input values given by name (`--input name=value`).
+	pub inputs: Vec<(String, String)>,
+	/// The runner input values given by position (after the run ref).
+	pub input_args: Vec<String>,
+}
 
+/// Run the runners of a run ref, and returns the session exit code.
+#[tokio::main]
+pub async fn run(run_ref: &str, options: RunOptions) -> Result<i32> {
 	// -- Parse the "Awesome.toml"
 	// TODO: might want to check if "./" works on windows
 	let config = find_and_parse_awesome_toml(Path::new("./"))?;
 
 	// -- Compute the Runners
-	// If two parts, then, we have a group_name.runner_name
-	let runners = if let Some(part2) = part2 {
-		config.get_grouped_runner(part1, part2).map(|r| vec![r])
-	}
-	// otherwise, we just have a group or a solo runner
-	else {
-		config
-			.get_runners(part1)
-			.or_else(|| config.get_solo_runner(part1).map(|r| vec![r]))
-	};
+	let runners = config.get_runners_for_ref(run_ref);
 
 	// -- Run the runners
 	if let Some(runners) = runners {
-		run_runners(runners).await?;
+		let hooks = config.get_hooks_for_ref(run_ref);
+		// -- Resolve the runner inputs (prompted when in a terminal), as the initial session vars.
+		let vars = block_in_place(|| input_vars(&runners, &options, io::stdin().is_terminal()))?;
+		run_runners(&config, runners, hooks, vars, &options).await
 	} else {
-		println!("No runners found for '{run_ref}'");
+		Err(config.run_ref_not_found(run_ref))
 	}
+}
 
-	Ok(())
+/// Resolve the runner inputs (prompted when `interactive`), as the initial session vars.
+fn input_vars(runners: &[&Runner], options: &RunOptions, interactive: bool) -> Result<Vars> {
+	let inputs: Vec<_> = runners.iter().flat_map(|runner| runner.inputs.iter()).collect();
+	resolve_inputs(&inputs, &options.inputs, &options.input_args, interactive)
 }
 
-async fn run_runners(runners: Vec<&Runner>) -> Result<()> {
+async fn run_runners(
+	config: &Config,
+	runners: Vec<&Runner>,
+	hooks: Option<&Hooks>,
+	vars: Vars,
+	options: &RunOptions,
+) -> Result<i32> {
 	// TODO: needs to get it from the params.
 	let root_dir = Path::new(".");
 
-	// Vec to keep track of the concurrent processes.
-	struct RunnerConcurrentSpawn {
-		name: String,
-		child: Child,
-		end_all_on_exit: bool,
+	let (exit_tx, mut exit_rx) = mpsc::unbounded_channel::<ExitEvent>();
+	let mut session = Session::new(config, root_dir, options, vars, exit_tx);
+	let records: Vec<usize> = runners.iter().map(|runner| session.summary.add(runner)).collect();
+
+	// --- Exec the before hooks, and then, each runner (until Ctrl-C).
+	// Note: `None` when interrupted.
+	let started = tokio::select! {
+		started = session.start(&runners, &records, hooks) => Some(started),
+		_ = signal::ctrl_c() => None,
+	};
+
+	// --- Process the concurrent runner exits which happened during the start.
+	while let Ok(exit_event) = exit_rx.try_recv() {
+		session.on_exit(exit_event).await;
+	}
+
+	// --- If the start got interrupted or aborted (failed runner), stop the already started runners.
+	match started {
+		Some(true) => (),
+		Some(false) if session.deadline_passed() => session.session_timeout(),
+		Some(false) => session.end_all(),
+		None => session.interrupt(),
+	}
+	// Note: The sequential runners left running by the dropped start (e.g., a build on Ctrl-C).
+	session.stop_in_flight().await;
+
+	// --- Supervise the concurrent runners, and apply their on_exit action when they exit.
+	loop {
+		let running = session.spawns.iter().filter(|s| s.supervised.is_some()).count();
+		let next_restart_at = session.spawns.iter().filter_map(|s| s.restart_at).min();
+		let deadline = session.deadline.filter(|_| !session.ending);
+		if running == 0 && (session.ending || next_restart_at.is_none()) {
+			break;
+		}
+
+		tokio::select! {
+			Some(exit_event) = exit_rx.recv() => {
+				session.on_exit(exit_event).await;
+			}
+
+			// -- Ctrl-C, stop all of the runners (a second Ctrl-C exits right away).
+			_ = signal::ctrl_c() => {
+				if session.ending {
+					println!("Ctrl-C - Exiting without waiting for the runners to stop.");
+					std::process::exit(CTRL_C_EXIT_CODE);
+				}
+				session.interrupt();
+			}
+
+			// -- Session timeout, stop all of the runners.
+			_ = sleep_until(deadline.unwrap_or_else(Instant::now).into()), if deadline.is_some() => {
+				session.session_timeout();
+			}
+
+			// -- Restart the children that are due.
+			_ = sleep_until(next_restart_at.unwrap_or_else(Instant::now).into()), if next_restart_at.is_some() => {
+				let now = Instant::now();
+				for idx in 0..session.spawns.len() {
+					if session.spawns[idx].restart_at.is_some_and(|at| at <= now) {
+						session.spawns[idx].restart_at = None;
+						session.restart(idx).await;
+					}
+				}
+			}
+		}
+	}
+
+	// --- Exec the end hooks, after or on_failure (unless interrupted), and then, finally (always).
+	// Note: Not bound by the session timeout (e.g., cleanup after a timeout).
+	session.summary.finish();
+	session.deadline = None;
+	if let Some(hooks) = hooks {
+		if !session.interrupted {
+			let end_hooks = async {
+				if !session.summary.is_failed() {
+					session.run_hooks(HookKind::After, &hooks.after).await;
+				}
+				if session.summary.is_failed() {
+					session.run_hooks(HookKind::OnFailure, &hooks.on_failure).await;
+				}
+			};
+			tokio::select! {
+				_ = end_hooks => (),
+				_ = signal::ctrl_c() => session.interrupt(),
+			}
+			session.stop_in_flight().await;
+		}
+		// Note: A Ctrl-C during the finally hooks exits right away.
+		tokio::select! {
+			_ = session.run_hooks(HookKind::Finally, &hooks.finally) => (),
+			_ = signal::ctrl_c() => {
+				println!("Ctrl-C - Exiting without running the remaining finally hooks.");
+				std::process::exit(CTRL_C_EXIT_CODE);
+			}
+		}
+	}
+
+	// --- The summary decides the exit code.
+	session.summary.finish();
+	session.summary.print();
+
+	Ok(session.summary.exit_code())
+}
+
+// region:    --- Session
+
+/// The state of a run session.
+struct Session<'a> {
+	config: &'a Config,
+	root_dir: &'a Path,
+	options: &'a RunOptions,
+	/// The concurrent runners spawned in this session (idx being their position).
+	spawns: Vec<RunnerSpawn<'a>>,
+	exit_tx: mpsc::UnboundedSender<ExitEvent>,
+	summary: Summary,
+	/// True once all of the runners are being stopped (end_all, Ctrl-C, failure, timeout).
+	ending: bool,
+	/// From the session `--timeout`.
+	deadline: Option<Instant>,
+	/// The session vars (e.g., from the runner `inputs` and the `capture` runners).
+	vars: Vars,
+	/// The run refs of the runners read by others (`stdin = { from = "..." }`), which get their stdout buffered.
+	stdin_froms: HashSet<String>,
+	/// The stdout of the `stdin_froms` runners ran in this session (of their last run).
+	/// Note: RefCell, as the parallel runners add their buffers while the others are running.
+	outputs: RefCell<HashMap<String, StdoutBuffer>>,
+	/// The runner `mutex` locks, by name (created on first use).
+	mutexes: RefCell<HashMap<String, Arc<Mutex<()>>>>,
+	/// The log files of the runners, by run ref (opened on first use).
+	logs: RefCell<HashMap<String, SharedLog>>,
+	/// True once interrupted by Ctrl-C.
+	interrupted: bool,
+	/// The sequential runner processes being awaited (still running if their wait got dropped, e.g., on Ctrl-C).
+	in_flight: InFlight,
+}
+
+impl<'a> Session<'a> {
+	fn new(
+		config: &'a Config,
+		root_dir: &'a Path,
+		options: &'a RunOptions,
+		vars: Vars,
+		exit_tx: mpsc::UnboundedSender<ExitEvent>,
+	) -> Self {
+		Session {
+			config,
+			root_dir,
+			options,
+			spawns: Vec::new(),
+			exit_tx,
+			summary: Summary::default(),
+			ending: false,
+			deadline: options.timeout.map(|t| Instant::now() + t),
+			vars,
+			stdin_froms: config
+				.all_runners()
+				.filter_map(|runner| match &runner.stdin {
+					Some(Stdin::From(run_ref)) => Some(run_ref.to_string()),
+					_ => None,
+				})
+				.collect(),
+			outputs: RefCell::default(),
+			mutexes: RefCell::default(),
+			logs: RefCell::default(),
+			interrupted: false,
+			in_flight: InFlight::default(),
+		}
+	}
+
+	/// Build the output pipe of a runner, buffering its stdout when read by other runners.
+	fn output_pipe(&self, runner: &Runner, idx: usize) -> OutputPipe {
+		let mut output_pipe = build_output_pipe(self.config, runner, idx, self.runner_log(runner));
+		let run_ref = runner.run_ref();
+		if self.stdin_froms.contains(&run_ref) {
+			let buffer = StdoutBuffer::default();
+			output_pipe.stdout_buffer = Some(buffer.clone());
+			self.outputs.borrow_mut().insert(run_ref, buffer);
+		}
+		output_pipe
+	}
+
+	/// The log file of a runner (when logged), shared by all of its runs in the session.
+	/// Note: The sequential runners are only logged with `[logs] sequential = true`, as it pipes their output.
+	fn runner_log(&self, runner: &Runner) -> Option<SharedLog> {
+		let logs_config = &self.config.logs;
+		let piped = runner.concurrent || runner.parallel > 1;
+		if !logs_config.enabled || !(piped || logs_config.sequential) {
+			return None;
+		}
+
+		let run_ref = runner.run_ref();
+		if let Some(log) = self.logs.borrow().get(&run_ref) {
+			return Some(log.clone());
+		}
+		match RunnerLog::open(self.root_dir, logs_config, runner.group.as_deref(), &runner.name) {
+			Ok(log) => {
+				let log: SharedLog = Arc::new(log.into());
+				self.logs.borrow_mut().insert(run_ref, log.clone());
+				Some(log)
+			}
+			Err(ex) => {
+				println!("Warning - cannot open log file for runner {}. Cause: {ex}", runner.name);
+				None
+			}
+		}
+	}
+
+	/// Lock the named mutexes of a runner, in name order, so that runners sharing several of them cannot deadlock.
+	async fn lock_mutexes(&self, runner: &Runner, names: BTreeSet<String>) -> Vec<OwnedMutexGuard<()>> {
+		let mut guards = Vec::new();
+		for name in names {
+			let mutex = self.mutexes.borrow_mut().entry(name.to_string()).or_default().clone();
+			let guard = match mutex.clone().try_lock_owned() {
+				Ok(guard) => guard,
+				Err(_) => {
+					println!("Runner '{}' waiting for mutex '{name}'", runner.name);
+					mutex.lock_owned().await
+				}
+			};
+			guards.push(guard);
+		}
+		guards
 	}
-	let mut children_to_watch: Vec<RunnerConcurrentSpawn> = Vec::new();
 
-	// --- Exec each runner.
-	for runner in runners.iter() {
-		println!("==== Running runner: {}", runner.name);
+	fn deadline_passed(&self) -> bool {
+		self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+	}
+
+	/// Exec the before hooks (if any), and then, the runners (see `start_runners`).
+	async fn start(&mut self, runners: &[&'a Runner], records: &[usize], hooks: Option<&Hooks>) -> bool {
+		if let Some(hooks) = hooks {
+			if !self.run_hooks(HookKind::Before, &hooks.before).await {
+				self.summary.skip_pending("before hook failed");
+				return false;
+			}
+		}
+		self.start_runners(runners, records).await
+	}
+
+	/// Exec each runner in order. The concurrent ones get supervised (i.e., added to `spawns`).
+	/// Returns false when a runner failed without `continue_on_error` (and `--keep-going`).
+	async fn start_runners(&mut self, runners: &[&'a Runner], records: &[usize]) -> bool {
+		let mut i = 0;
+		while i < runners.len() {
+			if self.deadline_passed() {
+				self.summary.skip_pending("session timeout");
+				return false;
+			}
+
+			// -- Run the consecutive matrix entries of a parallel runner together.
+			let batch_len = parallel_batch_len(&runners[i..]);
+			if batch_len > 1 {
+				let batch: Vec<(&Runner, usize)> = runners[i..i + batch_len]
+					.iter()
+					.copied()
+					.zip(records[i..i + batch_len].iter().copied())
+					.collect();
+				if let Some(failed) = self.start_parallel(&batch).await {
+					self.summary.skip_pending(&format!("'{failed}' failed"));
+					return false;
+				}
+				i += batch_len;
+				continue;
+			}
+
+			let (runner, record) = (runners[i], records[i]);
+			i += 1;
+			println!("==== Running runner: {}", runner.name);
+
+			if let Err(ex) = self.start_runner(runner, record).await {
+				println!("Runner '{}' failed. Cause: {ex}", runner.name);
+				self.summary.end(record, RunStatus::from_error(&ex));
+				if !runner.continue_on_error && !self.options.keep_going {
+					self.summary.skip_pending(&format!("'{}' failed", runner.name));
+					return false;
+				}
+			}
+		}
+
+		true
+	}
+
+	/// Exec the (sequential) entries of a matrix runner, up to `parallel` at a time, until all complete.
+	/// Returns the name of the first entry which failed the session (without `continue_on_error` and `--keep-going`).
+	async fn start_parallel(&mut self, batch: &[(&Runner, usize)]) -> Option<String> {
+		let parallel = batch[0].0.parallel;
+
+		// -- Check the entries to run first (one confirm question at a time).
+		let mut should_runs = Vec::new();
+		for (runner, record) in batch.iter().copied() {
+			should_runs.push(self.should_run(runner, record).await);
+		}
+
+		// Note: A distinct output idx (color) per entry, after the ones of the concurrent runners.
+		let first_idx = self.spawns.len();
+		let this = &*self;
+		let results: Vec<_> = stream::iter(batch.iter().copied().zip(should_runs).enumerate())
+			.map(|(i, ((runner, record), should_run))| async move {
+				let should_run = match should_run {
+					Ok(ShouldRun::No(reason)) => {
+						println!("Skip running runner '{}' because {reason}", runner.name);
+						return (runner, record, None, Ok(ExecOutput::Done), 1, Some(reason));
+					}
+					should_run => should_run,
+				};
+				println!("==== Running runner: {}", runner.name);
+				let started = Instant::now();
+				let (res, attempts) = match should_run {
+					Ok(_) => this.exec_with_retry(runner, first_idx + i, &[]).await,
+					Err(ex) => (Err(ex), 1),
+				};
+				if let Err(ex) = &res {
+					println!("Runner '{}' failed. Cause: {ex}", runner.name);
+				}
+				(runner, record, Some(started), res, attempts, None)
+			})
+			.buffer_unordered(parallel)
+			.collect()
+			.await;
+
+		// -- Record the results.
+		let mut failed = None;
+		for (runner, record, started, res, attempts, skipped) in results {
+			if let Some(reason) = skipped {
+				self.summary.end(record, RunStatus::Skipped(reason));
+				continue;
+			}
+			if let Some(started) = started {
+				self.summary.start_at(record, started);
+			}
+			self.summary.set_attempts(record, attempts);
+			match res {
+				Ok(ExecOutput::Captured(value)) => {
+					self.set_captured(runner, value);
+					self.summary.end(record, RunStatus::Ok);
+				}
+				// Note: Concurrent runners do not run in parallel batches.
+				Ok(ExecOutput::Done | ExecOutput::Spawned(_)) => self.summary.end(record, RunStatus::Ok),
+				Err(ex) => {
+					self.summary.end(record, RunStatus::from_error(&ex));
+					if !runner.continue_on_error && !self.options.keep_going && failed.is_none() {
+						failed = Some(runner.name.to_string());
+					}
+				}
+			}
+		}
+
+		failed
+	}
 
-		match runner.should_run(root_dir)? {
-			ShouldRun::No(reason) => println!("Skip running runner '{}' because {reason}", runner.name),
+	async fn start_runner(&mut self, runner: &'a Runner, record: usize) -> Result<()> {
+		match self.should_run(runner, record).await? {
+			ShouldRun::No(reason) => {
+				println!("Skip running runner '{}' because {reason}", runner.name);
+				self.summary.end(record, RunStatus::Skipped(reason));
+			}
 			ShouldRun::Yes => {
+				if let Some(for_each) = &runner.for_each {
+					return self.start_for_each(runner, for_each, record).await;
+				}
+
 				// exec the runner.
 				// returns a child if process is concurrent.
-				let child = runner.exec().await?;
+				self.summary.start(record);
+				let idx = self.spawns.len();
 
-				// if concurrent, keep an eye on this child.
-				if let Some(child) = child {
-					children_to_watch.push(RunnerConcurrentSpawn {
-						name: runner.name.to_string(),
-						child,
-						end_all_on_exit: runner.end_all_on_exit,
-					});
+				let (exec_res, attempts) = self.exec_with_retry(runner, idx, &[]).await;
+				self.summary.set_attempts(record, attempts);
+				match exec_res? {
+					ExecOutput::Done => self.summary.end(record, RunStatus::Ok),
+					ExecOutput::Captured(value) => {
+						self.set_captured(runner, value);
+						self.summary.end(record, RunStatus::Ok);
+					}
+					// if concurrent, wait for it to be ready (when ready_when), and supervise this child.
+					ExecOutput::Spawned(mut runner_child) => {
+						let ready = runner.wait_ready(self.root_dir, &self.vars, &mut runner_child);
+						let ready_res = match self.deadline {
+							Some(deadline) => timeout_at(deadline.into(), ready).await.unwrap_or_else(|_| {
+								Err(Error::ExecTimeout(
+									runner.name.to_string(),
+									self.options.timeout.unwrap_or_default(),
+								))
+							}),
+							None => ready.await,
+						};
+						// Note: Supervised even if not ready, so that it gets stopped with the others.
+						let checks = runner_child.checks;
+						let supervised = supervise(
+							idx,
+							runner,
+							runner_child.child,
+							runner_child.pumps,
+							self.exit_tx.clone(),
+						);
+						self.spawns.push(RunnerSpawn::new(runner, supervised, checks, record));
+						ready_res?;
+					}
 				}
 			}
 		}
+
+		Ok(())
 	}
 
-	// --- Watch processes when concurrent to end_all_on_exit when flagged.
-	// TODO: Probably need to change that to avoid doing polling.
-	//       Strategy: Tokio Spawn for the child with mpsc for the end_all event.
-	if !children_to_watch.is_empty() {
-		let mut end_all = false;
+	/// The runner `should_run`, and then, its `confirm` question (if any), answered by the user, or with `--yes`,
+	/// or with the `confirm_default` when stdin is not a terminal (e.g., in CI). The answer goes to the summary.
+	async fn should_run(&mut self, runner: &Runner, record: usize) -> Result<ShouldRun> {
+		let should_run = runner.should_run(self.root_dir)?;
+		let (ShouldRun::Yes, Some(question)) = (&should_run, &runner.confirm) else {
+			return Ok(should_run);
+		};
+		let default_yes = runner.confirm_default == ConfirmDefault::Yes;
 
-		let mut sys = System::new();
+		// -- Get the answer.
+		let (confirmed, auto) = if self.options.yes {
+			(true, Some("--yes"))
+		} else if !io::stdin().is_terminal() {
+			(default_yes, Some("no tty"))
+		} else {
+			let hint = if default_yes { "[Y/n]" } else { "[y/N]" };
+			let answer = prompt_async(&format!("{question} {hint} "), None).await?;
+			let confirmed = match answer.to_lowercase().as_str() {
+				"" => default_yes,
+				answer => answer == "y" || answer == "yes",
+			};
+			(confirmed, None)
+		};
 
-		'main: loop {
-			// --- Check if any children is down.
-			for RunnerConcurrentSpawn {
-				child, end_all_on_exit, ..
-			} in children_to_watch.iter_mut()
-			{
-				let status = child.try_wait()?;
-				if status.is_some() && *end_all_on_exit {
-					end_all = true;
-				}
+		// -- Record the answer.
+		let answer = if confirmed { "yes" } else { "no" };
+		let confirm = match auto {
+			Some(auto) => {
+				println!("{question} {answer} ({auto})");
+				format!("{question} {answer}, {auto}")
 			}
+			None => format!("{question} {answer}"),
+		};
+		self.summary.set_confirm(record, confirm);
 
-			// --- If end_all true, then, we terminate all.
-			if end_all {
-				for RunnerConcurrentSpawn { name, child, .. } in children_to_watch.iter_mut() {
-					if (child.try_wait()?).is_none() {
-						terminate_process_tree(&mut sys, name, child).await?
-					}
+		match (confirmed, runner.confirm_default) {
+			(true, _) => Ok(ShouldRun::Yes),
+			(false, ConfirmDefault::Abort) => Err(Error::ConfirmAborted(runner.name.to_string())),
+			(false, _) => Ok(ShouldRun::No("not confirmed".to_string())),
+		}
+	}
+
+	/// Exec a (sequential) `for_each` runner once per batch of items, up to `parallel` at a time, until all complete.
+	async fn start_for_each(&mut self, runner: &Runner, for_each: &ForEach, record: usize) -> Result<()> {
+		if runner.concurrent {
+			return Err(Error::ForEachInvalid(
+				runner.name.to_string(),
+				"not supported for concurrent runners".to_string(),
+			));
+		}
+		let items = block_in_place(|| for_each.items(&runner.name, &self.vars))?;
+		if items.is_empty() {
+			println!("Skip running runner '{}' because for_each has no items", runner.name);
+			self.summary.end(record, RunStatus::Skipped("no for_each items".to_string()));
+			return Ok(());
+		}
+
+		// -- Exec the batches (results in the items order).
+		self.summary.start(record);
+		// Note: A distinct output idx (color) per batch, after the ones of the concurrent runners.
+		let first_idx = self.spawns.len();
+		let this = &*self;
+		let results: Vec<_> = stream::iter(items.chunks(for_each.batch.max(1)).enumerate())
+			.map(|(i, batch)| async move {
+				println!("==== Running runner: {} ({})", runner.name, batch.join(" "));
+				let (res, attempts) = this.exec_with_retry(runner, first_idx + i, batch).await;
+				if let Err(ex) = &res {
+					println!("Runner '{}' failed for '{}'. Cause: {ex}", runner.name, batch.join(" "));
 				}
-				break 'main;
+				(batch, res, attempts)
+			})
+			.buffered(runner.parallel.max(1))
+			.collect()
+			.await;
+
+		// -- Record the results (the first failure being the cause).
+		let mut failed_items = 0;
+		let mut first_failure = None;
+		for (batch, res, attempts) in results {
+			if attempts > 1 {
+				self.summary.set_attempts(record, attempts);
 			}
+			match res {
+				Ok(ExecOutput::Captured(value)) => self.set_captured(runner, value),
+				Ok(_) => (),
+				Err(ex) => {
+					failed_items += batch.len();
+					first_failure.get_or_insert((batch.join(" "), ex));
+				}
+			}
+		}
 
-			sleep(Duration::from_millis(WATCH_CHILD_DELAY)).await;
+		match first_failure {
+			Some((item, cause)) => Err(Error::ForEachFailed(failed_items, items.len(), item, Box::new(cause))),
+			None => {
+				self.summary.end(record, RunStatus::Ok);
+				Ok(())
+			}
 		}
 	}
 
-	Ok(())
-}
+	/// Exec the runner (with the for_each `items`, if any), and retry it (per its `retry`) while it fails.
+	/// Returns the result with the number of attempts.
+	async fn exec_with_retry(&self, runner: &Runner, idx: usize, items: &[String]) -> (Result<ExecOutput>, u32) {
+		let max_attempts = runner.retry.as_ref().map(|r| r.attempts).unwrap_or(1);
+		let mut attempt = 1;
+		loop {
+			let guards = self.lock_mutexes(runner, runner.mutex_names(&self.vars, items)).await;
+			let output_pipe = self.output_pipe(runner, idx);
+			if attempt > 1 {
+				output_pipe.write_log(&format!("==== attempt {attempt}/{max_attempts}"));
+			}
+
+			let ctx = ExecContext {
+				root_dir: self.root_dir,
+				deadline: self.deadline,
+				in_flight: &self.in_flight,
+				vars: &self.vars,
+				outputs: &self.outputs,
+				items,
+			};
+			let err = match runner.exec(output_pipe, &ctx).await {
+				Ok(exec_output) => return (Ok(exec_output), attempt),
+				Err(err) => err,
+			};
 
-/// Terminate this process and all of its children.
-async fn terminate_process_tree(sys: &mut System, name: &str, proc: &mut Child) -> Result<()> {
-	if let Some(proc_id) = proc.id() {
-		let proc_pid = Pid::from_u32(proc_id);
+			// -- Retry, unless not retryable (or the session timed out).
+			let delay = runner.retry.as_ref().and_then(|retry| retry.next_delay(attempt, &err));
+			let Some(delay) = delay.filter(|_| !self.deadline_passed()) else {
+				return (Err(err), attempt);
+			};
+			// Note: Released before the retry delay, so that the other runners can use the resources.
+			drop(guards);
+			println!(
+				"Runner '{}' attempt {attempt}/{max_attempts} failed. Cause: {err}. Retrying in {}.",
+				runner.name,
+				format_duration(delay)
+			);
+			sleep(delay).await;
+			attempt += 1;
+			println!(
+				"==== Running runner: {} (attempt {attempt}/{max_attempts})",
+				runner.name
+			);
+		}
+	}
 
-		// --- Fetch the children
-		sys.refresh_processes_specifics(ProcessRefreshKind::everything().without_cpu());
-		let sys_processes = sys.processes();
-		let children = find_descendant(sys_processes, &proc_pid);
+	/// Set the value captured by a `capture` runner as a session var.
+	fn set_captured(&mut self, runner: &Runner, value: String) {
+		if let Some(capture) = &runner.capture {
+			println!("Captured {} = '{value}'", capture.var);
+			self.vars.insert(capture.var.to_string(), value);
+		}
+	}
+
+	/// Record the exit of a concurrent runner, and apply its exit action.
+	async fn on_exit(&mut self, exit_event: ExitEvent) {
+		let ExitEvent {
+			idx,
+			status,
+			timed_out,
+			at,
+		} = exit_event;
+		let spawn = &mut self.spawns[idx];
+		spawn.supervised = None;
+		let runner = spawn.runner;
+		let record = spawn.record;
+
+		if timed_out {
+			let timeout = runner.timeout.unwrap_or_default();
+			self.summary.end_at(record, RunStatus::Timeout(timeout), at);
+		}
+
+		// when ending all, we just wait for all of the children to exit.
+		if self.ending {
+			self.summary.end_at(record, RunStatus::Killed, at);
+			return;
+		}
 
-		// --- Terminate the parent
-		match proc.kill().await {
-			Ok(_) => (),
-			Err(ex) => println!("Warning - error while stopping runner {name}. Cause: {ex}"),
+		let status = match status {
+			Ok(status) => status,
+			Err(ex) => {
+				println!("Warning - fail to wait for runner '{}'. Cause: {ex}", runner.name);
+				let failed = RunStatus::Failed {
+					code: None,
+					reason: ex.to_string(),
+				};
+				self.summary.end_at(record, failed, at);
+				return;
+			}
 		};
+		// -- Check the exit (success_codes, output checks), and record it.
+		let exit_res = runner.check_exit(&status, &spawn.checks);
+		match &exit_res {
+			Ok(()) => self.summary.end_at(record, RunStatus::Ok, at),
+			Err(err) => {
+				if status.success() {
+					println!("Runner '{}' failed. Cause: {err}", runner.name);
+				}
+				self.summary.end_at(record, RunStatus::from_error(err), at);
+			}
+		}
 
-		// --- Terminate the children
-		for (pid, _) in children {
-			if let Some(process) = sys.process(pid) {
-				let _ = process.kill();
+		match runner.exit_action(exit_res.is_ok()) {
+			ExitAction::Ignore => (),
+			ExitAction::EndAll => {
+				println!("Runner '{}' exited ({status}). Ending all runners.", runner.name);
+				// Note: The session ends with the code of the runner (non-zero when it failed its checks).
+				self.summary.set_exit_code(match status_code(&status) {
+					0 if exit_res.is_err() => 1,
+					code => code,
+				});
+				self.end_all();
+			}
+			ExitAction::FailSession => {
+				println!("Runner '{}' exited ({status}). Failing the session.", runner.name);
+				self.summary.set_exit_code(match status_code(&status) {
+					0 => 1,
+					code => code,
+				});
+				self.end_all();
+			}
+			ExitAction::Restart if spawn.restarts >= runner.max_restarts => {
+				println!(
+					"Runner '{}' exited ({status}). Not restarting, max_restarts ({}) reached.",
+					runner.name, runner.max_restarts
+				);
+			}
+			ExitAction::Restart => {
+				let delay = runner.restart_delay(spawn.restarts);
+				spawn.restarts += 1;
+				println!(
+					"Runner '{}' exited ({status}). Restarting in {}ms (restart {}/{})",
+					runner.name,
+					delay.as_millis(),
+					spawn.restarts,
+					runner.max_restarts
+				);
+				spawn.restart_at = Some(Instant::now() + delay);
+				spawn.last_status = Some(status);
+			}
+			ExitAction::Run(run_ref) => {
+				println!("Runner '{}' exited ({status}). Running '{run_ref}'.", runner.name);
+				self.run_follow_up(&run_ref).await;
 			}
 		}
 	}
 
-	Ok(())
-}
+	/// The session `--timeout` passed, the running runners get stopped (and reported as timed out).
+	fn session_timeout(&mut self) {
+		let timeout = self.options.timeout.unwrap_or_default();
+		println!(
+			"Session timeout ({}) - Stopping all runners.",
+			humantime::format_duration(timeout)
+		);
+		self.summary.end_running(RunStatus::Timeout(timeout));
+		self.end_all();
+	}
+
+	/// Ctrl-C, stop all of the runners.
+	fn interrupt(&mut self) {
+		println!("Ctrl-C - Stopping all runners.");
+		self.interrupted = true;
+		self.summary.set_exit_code(CTRL_C_EXIT_CODE);
+		self.end_all();
+	}
 
-fn find_descendant(sys_processes: &HashMap<Pid, Process>, root_pid: &Pid) -> Vec<(Pid, String)> {
-	let mut children: HashMap<Pid, String> = HashMap::new();
+	/// Terminate the sequential runners whose wait got dropped, and wait for them to exit
+	/// (a second Ctrl-C exits right away).
+	/// Note: Their records stay running until then, to be reported as killed by the summary `finish`.
+	async fn stop_in_flight(&self) {
+		tokio::select! {
+			_ = self.in_flight.terminate_all() => (),
+			_ = signal::ctrl_c() => {
+				println!("Ctrl-C - Exiting without waiting for the runners to stop.");
+				std::process::exit(CTRL_C_EXIT_CODE);
+			}
+		}
+	}
 
-	// NOTE: For now, going a little brute force, but this should be exhaustive
-	//       and does not really have significant performance impact for the usecase.
-	'main: loop {
-		let mut cycle_has = false;
-		for (pid, p) in sys_processes.iter() {
-			if let Some(parent_pid) = p.parent() {
-				if !children.contains_key(pid) && (parent_pid == *root_pid || children.contains_key(&parent_pid)) {
-					children.insert(*pid, p.name().to_string());
-					cycle_has = true;
+	/// Exec the hook runners, one after the other, to completion (the concurrent ones get awaited).
+	/// Returns false when one failed (without `continue_on_error`), which stops this hook, except for `finally`.
+	async fn run_hooks(&mut self, hook: HookKind, run_refs: &[String]) -> bool {
+		let config = self.config;
+		let mut ok = true;
+		for run_ref in run_refs {
+			let Some(runners) = config.get_runners_for_ref(run_ref) else {
+				println!("Warning - {hook} hook '{run_ref}' does not match any runner.");
+				continue;
+			};
+			for runner in runners {
+				println!("==== Running {hook} hook: {}", runner.name);
+				let record = self.summary.add_hook(runner, hook);
+				let res = match self.should_run(runner, record).await {
+					Ok(ShouldRun::No(reason)) => {
+						println!("Skip running runner '{}' because {reason}", runner.name);
+						self.summary.end(record, RunStatus::Skipped(reason));
+						continue;
+					}
+					Ok(ShouldRun::Yes) => self.run_hook(runner, record).await,
+					Err(ex) => Err(ex),
+				};
+				match res {
+					Ok(()) => self.summary.end(record, RunStatus::Ok),
+					Err(ex) => {
+						println!("{hook} hook '{}' failed. Cause: {ex}", runner.name);
+						self.summary.end(record, RunStatus::from_error(&ex));
+						if !runner.continue_on_error {
+							ok = false;
+							if hook != HookKind::Finally {
+								return false;
+							}
+						}
+					}
 				}
 			}
 		}
-		// if this cycle did not find anything, we can break the search.
-		if !cycle_has {
-			break 'main;
+		ok
+	}
+
+	async fn run_hook(&mut self, runner: &Runner, record: usize) -> Result<()> {
+		self.summary.start(record);
+		let (exec_res, attempts) = self.exec_with_retry(runner, self.spawns.len(), &[]).await;
+		self.summary.set_attempts(record, attempts);
+		match exec_res? {
+			ExecOutput::Done => (),
+			ExecOutput::Captured(value) => self.set_captured(runner, value),
+			ExecOutput::Spawned(mut runner_child) => {
+				let status = runner_child.child.wait().await?;
+				for pump in runner_child.pumps {
+					let _ = pump.await;
+				}
+				runner.check_exit(&status, &runner_child.checks)?;
+			}
 		}
+		Ok(())
 	}
 
-	children.into_iter().collect()
+	/// Stop all of the running children (and cancel the pending restarts).
+	fn end_all(&mut self) {
+		self.ending = true;
+		for spawn in self.spawns.iter_mut() {
+			spawn.restart_at = None;
+			if let Some(supervised) = spawn.supervised.as_mut() {
+				supervised.stop();
+			}
+		}
+	}
+
+	/// Re-spawn the child of a concurrent runner through `Runner::exec` (per its restart policy).
+	async fn restart(&mut self, idx: usize) {
+		let runner = self.spawns[idx].runner;
+		let output_pipe = self.output_pipe(runner, idx);
+		let spawn = &mut self.spawns[idx];
+		let status = spawn.last_status.map(|s| s.to_string()).unwrap_or_default();
+		output_pipe.write_log(&format!(
+			"==== restart {}/{} after exit ({status})",
+			spawn.restarts, runner.max_restarts
+		));
+		let ctx = ExecContext {
+			root_dir: self.root_dir,
+			deadline: self.deadline,
+			in_flight: &self.in_flight,
+			vars: &self.vars,
+			outputs: &self.outputs,
+			items: &[],
+		};
+		match runner.exec(output_pipe, &ctx).await {
+			Ok(ExecOutput::Spawned(runner_child)) => {
+				spawn.checks = runner_child.checks;
+				spawn.supervised = Some(supervise(
+					idx,
+					runner,
+					runner_child.child,
+					runner_child.pumps,
+					self.exit_tx.clone(),
+				));
+				self.summary.restart(spawn.record);
+			}
+			Ok(ExecOutput::Done | ExecOutput::Captured(_)) => (),
+			Err(ex) => println!("Warning - fail to restart runner '{}'. Cause: {ex}", runner.name),
+		}
+	}
+
+	/// Run the follow-up runners of an `on_exit` `run = "..."` action (the concurrent ones get supervised as well).
+	async fn run_follow_up(&mut self, run_ref: &str) {
+		let config = self.config;
+		let Some(runners) = config.get_runners_for_ref(run_ref) else {
+			println!("Warning - on_exit run = '{run_ref}' does not match any runner.");
+			return;
+		};
+		for runner in runners {
+			println!("==== Running runner: {}", runner.name);
+			let record = self.summary.add(runner);
+			self.summary.start(record);
+			let idx = self.spawns.len();
+			let output_pipe = self.output_pipe(runner, idx);
+			let ctx = ExecContext {
+				root_dir: self.root_dir,
+				deadline: self.deadline,
+				in_flight: &self.in_flight,
+				vars: &self.vars,
+				outputs: &self.outputs,
+				items: &[],
+			};
+			match runner.exec(output_pipe, &ctx).await {
+				Ok(ExecOutput::Spawned(runner_child)) => {
+					let checks = runner_child.checks;
+					let supervised = supervise(
+						idx,
+						runner,
+						runner_child.child,
+						runner_child.pumps,
+						self.exit_tx.clone(),
+					);
+					self.spawns.push(RunnerSpawn::new(runner, supervised, checks, record));
+				}
+				Ok(ExecOutput::Done) => self.summary.end(record, RunStatus::Ok),
+				Ok(ExecOutput::Captured(value)) => {
+					self.set_captured(runner, value);
+					self.summary.end(record, RunStatus::Ok);
+				}
+				Err(ex) => {
+					println!("Warning - runner '{}' failed. Cause: {ex}", runner.name);
+					self.summary.end(record, RunStatus::from_error(&ex));
+				}
+			}
+		}
+	}
+}
+
+/// A concurrent runner spawned in the session.
+struct RunnerSpawn<'a> {
+	runner: &'a Runner,
+	/// The supervised child, None when the child exited.
+	supervised: Option<Supervised>,
+	/// The output checks of the current child (checked when it exits).
+	checks: OutputChecks,
+	/// The index of the runner record in the session summary.
+	record: usize,
+	/// Number of restarts done so far (from the runner restart policy).
+	restarts: u32,
+	/// When the child exited and should be restarted, the time to restart it.
+	restart_at: Option<Instant>,
+	last_status: Option<ExitStatus>,
+}
+
+impl<'a> RunnerSpawn<'a> {
+	fn new(runner: &'a Runner, supervised: Supervised, checks: OutputChecks, record: usize) -> Self {
+		RunnerSpawn {
+			runner,
+			supervised: Some(supervised),
+			checks,
+			record,
+			restarts: 0,
+			restart_at: None,
+			last_status: None,
+		}
+	}
+}
+
+// endregion: --- Session
+
+/// The number of runners, at the start of `runners`, which are the matrix entries of the same parallel
+/// (sequential) runner.
+fn parallel_batch_len(runners: &[&Runner]) -> usize {
+	let Some(first) = runners.first() else {
+		return 0;
+	};
+	// Note: The for_each runners use `parallel` for their items.
+	if first.concurrent || first.parallel <= 1 || first.matrix_entry.is_none() || first.for_each.is_some() {
+		return 1;
+	}
+	let base = (first.group.as_deref(), first.matrix_base_name());
+	runners
+		.iter()
+		.take_while(|r| !r.concurrent && (r.group.as_deref(), r.matrix_base_name()) == base)
+		.count()
 }
+
+/// The process exit code (1 when terminated by a signal).
+fn status_code(status: &ExitStatus) -> i32 {
+	status.code().unwrap_or(1)
+}
+
+/// Build the output pipe of a runner, with the prefixed printer for concurrent runners,
+/// and its log file (if logged).
+fn build_output_pipe(config: &Config, runner: &Runner, concurrent_idx: usize, log: Option<SharedLog>) -> OutputPipe {
+	let printer = if runner.concurrent || runner.parallel > 1 {
+		let color = runner.color.unwrap_or(Color::PALETTE[concurrent_idx % Color::PALETTE.len()]);
+		LinePrinter::new(&config.output, &runner.name, color)
+	} else {
+		LinePrinter::plain()
+	};
+
+	OutputPipe::new(printer, log)
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_run_runners.rs"]
+mod tests;
+// endregion: --- Tests
@@ -0,0 +1,265 @@
+//! The end-of-run summary of a session (status and duration of each runner), which decides the session exit code.
+
+use crate::config::{HookKind, Runner};
+use crate::Error;
+use std::time::{Duration, Instant};
+
+/// Exit code when a runner (or the session) timed out (same as the `timeout` command).
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunStatus {
+	/// Not started (yet).
+	Pending,
+	Running,
+	Ok,
+	/// The exit code (None when terminated by a signal or not from the process, e.g., spawn error), and the reason.
+	Failed {
+		code: Option<i32>,
+		reason: String,
+	},
+	Skipped(String),
+	/// Terminated after passing its timeout (or the session timeout).
+	Timeout(Duration),
+	/// Stopped by the session (e.g., end_all, Ctrl-C).
+	Killed,
+}
+
+impl RunStatus {
+	pub fn from_error(err: &Error) -> Self {
+		match err {
+			Error::ExecStatus(_, status) => RunStatus::Failed {
+				code: status.code(),
+				reason: status.to_string(),
+			},
+			Error::ExecTimeout(_, duration) => RunStatus::Timeout(*duration),
+			// Note: The exit code of the first failed item, with the whole for_each message.
+			Error::ForEachFailed(.., cause) => match RunStatus::from_error(cause) {
+				RunStatus::Failed { code, .. } => RunStatus::Failed {
+					code,
+					reason: err.to_string(),
+				},
+				status => status,
+			},
+			_ => RunStatus::Failed {
+				code: None,
+				reason: err.to_string(),
+			},
+		}
+	}
+}
+
+pub struct RunRecord {
+	/// The run ref of the runner (`group_name.runner_name` or `solo_runner_name`).
+	pub name: String,
+	pub status: RunStatus,
+	/// When true, a failure of this runner does not fail the session.
+	pub continue_on_error: bool,
+	/// Number of attempts (more than 1 when retried).
+	pub attempts: u32,
+	/// For the hook runners, reported separately.
+	pub hook: Option<HookKind>,
+	/// The `confirm` question and its answer, e.g., `Reset local database? yes (--yes)`.
+	pub confirm: Option<String>,
+	started: Option<Instant>,
+	duration: Duration,
+}
+
+#[derive(Default)]
+pub struct Summary {
+	records: Vec<RunRecord>,
+	/// Set by the session (e.g., Ctrl-C, fail_session), takes precedence over the runner failures.
+	exit_code: Option<i32>,
+}
+
+impl Summary {
+	/// Add a (pending) record for this runner, and returns its index.
+	pub fn add(&mut self, runner: &Runner) -> usize {
+		self.records.push(RunRecord {
+			name: runner.run_ref(),
+			status: RunStatus::Pending,
+			continue_on_error: runner.continue_on_error,
+			attempts: 1,
+			hook: None,
+			confirm: None,
+			started: None,
+			duration: Duration::ZERO,
+		});
+		self.records.len() - 1
+	}
+
+	/// Same as `add`, for a hook runner.
+	pub fn add_hook(&mut self, runner: &Runner, hook: HookKind) -> usize {
+		let idx = self.add(runner);
+		self.records[idx].hook = Some(hook);
+		idx
+	}
+
+	/// True if the session failed so far (see `exit_code`).
+	pub fn is_failed(&self) -> bool {
+		self.exit_code() != 0
+	}
+
+	pub fn start(&mut self, idx: usize) {
+		self.start_at(idx, Instant::now());
+	}
+
+	/// Same as `start`, for a runner which started at `started_at` (e.g., in a parallel batch).
+	pub fn start_at(&mut self, idx: usize, started_at: Instant) {
+		let record = &mut self.records[idx];
+		record.status = RunStatus::Running;
+		record.started = Some(started_at);
+	}
+
+	/// Set the status of a restarted runner back to running (the duration includes all of the runs).
+	pub fn restart(&mut self, idx: usize) {
+		self.records[idx].status = RunStatus::Running;
+	}
+
+	pub fn set_confirm(&mut self, idx: usize, confirm: String) {
+		self.records[idx].confirm = Some(confirm);
+	}
+
+	pub fn set_attempts(&mut self, idx: usize, attempts: u32) {
+		self.records[idx].attempts = attempts;
+	}
+
+	/// Set the final status of a pending or running runner.
+	/// Note: A status already set (e.g., failed not ready) does not get overridden (e.g., by killed).
+	pub fn end(&mut self, idx: usize, status: RunStatus) {
+		self.end_at(idx, status, Instant::now());
+	}
+
+	/// Same as `end`, for a runner which ended at `ended_at`.
+	pub fn end_at(&mut self, idx: usize, status: RunStatus, ended_at: Instant) {
+		let record = &mut self.records[idx];
+		if matches!(record.status, RunStatus::Pending | RunStatus::Running) {
+			record.status = status;
+			record.duration = record.started.map(|s| ended_at.duration_since(s)).unwrap_or_default();
+		}
+	}
+
+	/// Set the final status of all of the running runners.
+	pub fn end_running(&mut self, status: RunStatus) {
+		for idx in 0..self.records.len() {
+			if self.records[idx].status == RunStatus::Running {
+				self.end(idx, status.clone());
+			}
+		}
+	}
+
+	/// Skip all of the runners not started yet.
+	pub fn skip_pending(&mut self, reason: &str) {
+		for record in self
+			.records
+			.iter_mut()
+			.filter(|r| r.status == RunStatus::Pending && r.hook.is_none())
+		{
+			record.status = RunStatus::Skipped(reason.to_string());
+		}
+	}
+
+	pub fn set_exit_code(&mut self, exit_code: i32) {
+		self.exit_code = Some(exit_code);
+	}
+
+	/// The session exit code, if not set by the session, the exit code of the first failed runner
+	/// (1 if it has none, 124 if timed out), ignoring the `continue_on_error` runners, and the `on_failure`
+	/// and `finally` hooks.
+	pub fn exit_code(&self) -> i32 {
+		if let Some(exit_code) = self.exit_code {
+			return exit_code;
+		}
+		self.records
+			.iter()
+			.filter(|r| !r.continue_on_error && r.hook.is_none_or(|hook| hook.fails_session()))
+			.find_map(|r| match &r.status {
+				RunStatus::Failed { code, .. } => Some(code.filter(|c| *c != 0).unwrap_or(1)),
+				RunStatus::Timeout(_) => Some(TIMEOUT_EXIT_CODE),
+				_ => None,
+			})
+			.unwrap_or(0)
+	}
+
+	/// End the records still running (interrupted) as killed, and skip the pending ones.
+	pub fn finish(&mut self) {
+		self.end_running(RunStatus::Killed);
+		self.skip_pending("not started");
+	}
+
+	pub fn print(&self) {
+		if self.records.is_empty() {
+			return;
+		}
+		let name_width = self.records.iter().map(|r| record_name(r).len()).max().unwrap_or(0);
+
+		let (hooks, runners): (Vec<&RunRecord>, Vec<&RunRecord>) = self.records.iter().partition(|r| r.hook.is_some());
+		println!("==== Summary");
+		print_records(&runners, name_width);
+		if !hooks.is_empty() {
+			println!("==== Hooks");
+			print_records(&hooks, name_width);
+		}
+	}
+}
+
+/// The name of the record in the summary, prefixed with the hook kind for hook runners (e.g., `finally: dev.down`).
+fn record_name(record: &RunRecord) -> String {
+	match record.hook {
+		Some(hook) => format!("{hook}: {}", record.name),
+		None => record.name.to_string(),
+	}
+}
+
+fn print_records(records: &[&RunRecord], name_width: usize) {
+	for record in records.iter() {
+		let (label, detail) = match &record.status {
+			RunStatus::Pending => ("skipped", "not started".to_string()),
+			RunStatus::Running => ("running", String::new()),
+			RunStatus::Ok => ("ok", String::new()),
+			RunStatus::Failed { reason, .. } => ("failed", reason.to_string()),
+			RunStatus::Skipped(reason) => ("skipped", reason.to_string()),
+			RunStatus::Timeout(duration) => ("timeout", format!("after {}", format_duration(*duration))),
+			RunStatus::Killed => ("killed", String::new()),
+		};
+		let mut notes = vec![detail];
+		if record.continue_on_error && matches!(record.status, RunStatus::Failed { .. } | RunStatus::Timeout(_)) {
+			notes.push("(continue_on_error)".to_string());
+		}
+		if record.attempts > 1 {
+			notes.push(format!("({} attempts)", record.attempts));
+		}
+		if let Some(confirm) = &record.confirm {
+			notes.push(format!("({confirm})"));
+		}
+		notes.retain(|n| !n.is_empty());
+		let detail = notes.join(" ");
+		let duration = match record.started {
+			Some(_) => format_duration(record.duration),
+			None => "-".to_string(),
+		};
+		let line = format!(
+			"{label:<8} {:<name_width$}  {duration:>8}  {detail}",
+			record_name(record)
+		);
+		println!("{}", line.trim_end());
+	}
+}
+
+/// e.g., `850ms`, `3.2s`, `2m05s`
+pub fn format_duration(duration: Duration) -> String {
+	let ms = duration.as_millis();
+	if ms < 1000 {
+		format!("{ms}ms")
+	} else if ms < 60_000 {
+		format!("{:.1}s", duration.as_secs_f64())
+	} else {
+		format!("{}m{:02}s", ms / 60_000, (ms / 1000) % 60)
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_summary.rs"]
+mod tests;
+// endregion: --- Tests
@@ -0,0 +1,94 @@
+//! Supervision of the concurrent runner processes.
+//! Each child is owned by its own task, which reports its exit over the session channel.
+
+use crate::config::Runner;
+use crate::proc_tree::terminate_process_tree;
+use std::io;
+use std::process::ExitStatus;
+use std::time::Instant;
+use tokio::process::Child;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::sleep_until;
+
+/// Sent by the supervisor task when its child exited (by itself or stopped).
+pub struct ExitEvent {
+	/// The index of the supervised runner in the session.
+	pub idx: usize,
+	pub status: io::Result<ExitStatus>,
+	/// True when the child got terminated because it passed the runner `timeout`.
+	pub timed_out: bool,
+	/// When the child exited (the event might get processed later).
+	pub at: Instant,
+}
+
+/// Handle to a supervised child.
+pub struct Supervised {
+	stop_tx: Option<oneshot::Sender<()>>,
+}
+
+impl Supervised {
+	/// Ask the supervisor to terminate the process tree.
+	/// The `ExitEvent` will still be sent once the child exited.
+	pub fn stop(&mut self) {
+		if let Some(stop_tx) = self.stop_tx.take() {
+			let _ = stop_tx.send(());
+		}
+	}
+}
+
+/// Spawn the supervisor task for the child of this runner (terminated when passing the runner `timeout`).
+/// The exit gets sent once its output `pumps` processed all of its lines (e.g., for the `fail_on_output` checks).
+pub fn supervise(
+	idx: usize,
+	runner: &Runner,
+	mut child: Child,
+	pumps: Vec<JoinHandle<()>>,
+	exit_tx: mpsc::UnboundedSender<ExitEvent>,
+) -> Supervised {
+	let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+	let name = runner.name.to_string();
+	let stop_signal = runner.stop_signal;
+	let stop_timeout = runner.stop_timeout;
+	let timeout = runner.timeout;
+	let deadline = timeout.map(|t| Instant::now() + t).unwrap_or_else(Instant::now);
+
+	tokio::spawn(async move {
+		let mut timed_out = false;
+		let status = tokio::select! {
+			status = child.wait() => status,
+			// Note: `Ok` only, so that a dropped handle does not stop the child.
+			Ok(()) = &mut stop_rx => {
+				terminate_process_tree(&name, &mut child, stop_signal, stop_timeout).await;
+				child.wait().await
+			}
+			_ = sleep_until(deadline.into()), if timeout.is_some() => {
+				println!(
+					"Runner '{name}' timed out after {}. Stopping it.",
+					humantime::format_duration(timeout.unwrap_or_default())
+				);
+				timed_out = true;
+				terminate_process_tree(&name, &mut child, stop_signal, stop_timeout).await;
+				child.wait().await
+			}
+		};
+		let at = Instant::now();
+		for pump in pumps {
+			let _ = pump.await;
+		}
+		let _ = exit_tx.send(ExitEvent {
+			idx,
+			status,
+			timed_out,
+			at,
+		});
+	});
+
+	Supervised { stop_tx: Some(stop_tx) }
+}
+
+// region:    --- Tests
+#[cfg(test)]
+#[path = "_tests/tests_supervisor.rs"]
+mod tests;
+// endregion: --- Tests
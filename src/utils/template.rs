@@ -0,0 +1,32 @@
+//! The session variables (e.g., from runner `capture`) and the `{{NAME}}` templates.
+
+use std::collections::HashMap;
+
+/// Session variables by name.
+pub type Vars = HashMap<String, String>;
+
+/// Replace the `{{NAME}}` (or `{{ NAME }}`) with the value of the var. Unknown vars are left as is.
+pub fn render_template(text: &str, vars: &Vars) -> String {
+	let mut res = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(start) = rest.find("{{") {
+		let Some(len) = rest[start..].find("}}") else {
+			break;
+		};
+		let end = start + len + 2;
+		res.push_str(&rest[..start]);
+		match vars.get(rest[start + 2..start + len].trim()) {
+			Some(value) => res.push_str(value),
+			None => res.push_str(&rest[start..end]),
+		}
+		rest = &rest[end..];
+	}
+	res.push_str(rest);
+	res
+}
+
+/// True if the text has the `{{name}}` (or `{{ name }}`) var.
+pub fn has_template_var(text: &str, name: &str) -> bool {
+	let vars = Vars::from([(name.to_string(), String::new())]);
+	render_template(text, &vars) != text
+}